@@ -1,22 +1,170 @@
 use std::fs;
+use std::io::Write;
 
 #[allow(unused_imports)]
-use git2::{AnnotatedCommit, BranchType, ObjectType, Oid, Repository, Sort, Status, StatusOptions, StashApplyOptions};
+use git2::{AnnotatedCommit, BranchType, Delta, DiffFindOptions, DiffOptions, ObjectType, Oid, Repository, Sort, Status, StatusOptions, StashApplyOptions};
 use serde::Serialize;
 use std::collections::HashMap;
+use tauri::Emitter;
+
+// Id of the virtual "Uncommitted Changes" node that get_commits puts on top of HEAD
+pub const WORKING_COPY_ID: &str = "working-copy";
 
 #[derive(Serialize)]
 pub struct GitCommit {
     id: String,
-    message: String,
+    message: String, // Summary line only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<String>, // The rest of the message, when there is one
     author: String,
-    date: i64,
+    author_email: String,
+    author_date: i64,
+    committer: String,
+    committer_email: String,
+    committer_date: i64,
+    date: i64, // Same as committer_date, kept for existing callers
     parents: Vec<String>,
     refs: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     head_type: Option<String>, // "detached" or "branch"
     #[serde(skip_serializing_if = "Option::is_none")]
     uncommitted_state: Option<String>, // "staged", "unstaged", or "mixed" for working-copy node
+    is_virtual: bool, // True for the working-copy node, which is not a real commit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent_summaries: Option<Vec<ParentSummary>>, // Only with get_commits(parent_summaries)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stats: Option<CommitStats>, // Working-copy node (unless too large) or get_commits(include_stats)
+}
+
+#[derive(Serialize)]
+pub struct CommitStats {
+    files_changed: usize,
+    additions: usize,
+    deletions: usize,
+}
+
+#[derive(Serialize)]
+pub struct ParentSummary {
+    oid: String,
+    summary: String,
+}
+
+// Glob patterns deciding which refs decorate commits in get_commits.
+// Patterns match either the full ref name (refs/remotes/origin/ci-*) or the short name
+// shown in the graph (origin/ci-*). A leading '!' excludes matching refs.
+struct RefFilter {
+    include: Vec<glob::Pattern>,
+    exclude: Vec<glob::Pattern>,
+}
+
+impl RefFilter {
+    fn parse(patterns: Vec<String>) -> Result<Self, String> {
+        let mut include = Vec::new();
+        let mut exclude = Vec::new();
+
+        for pattern in patterns {
+            let (list, raw) = match pattern.strip_prefix('!') {
+                Some(raw) => (&mut exclude, raw),
+                None => (&mut include, pattern.as_str()),
+            };
+            let compiled = glob::Pattern::new(raw)
+                .map_err(|e| format!("Invalid ref filter pattern '{}': {}", pattern, e))?;
+            list.push(compiled);
+        }
+
+        Ok(RefFilter { include, exclude })
+    }
+
+    fn is_visible(&self, full_name: &str, short_name: &str) -> bool {
+        let matches = |p: &glob::Pattern| p.matches(full_name) || p.matches(short_name);
+
+        // With only exclusions, everything else stays visible
+        let included = self.include.is_empty() || self.include.iter().any(matches);
+        included && !self.exclude.iter().any(matches)
+    }
+}
+
+// Glob patterns for paths to leave out of diffs and the working-copy status, e.g. generated
+// files. Like .gitignore, a pattern without a '/' matches the file or directory name at any
+// depth (*.lock, node_modules), while a pattern with a '/' matches the path from the
+// repository root (src/generated/*). Excluding a directory excludes everything below it.
+struct PathExcludes {
+    patterns: Vec<(glob::Pattern, bool)>, // (pattern, matches the name only)
+}
+
+impl PathExcludes {
+    fn parse(patterns: Vec<String>) -> Result<Self, String> {
+        let mut compiled = Vec::new();
+
+        for pattern in patterns {
+            let raw = pattern.trim().trim_start_matches('/').trim_end_matches('/');
+            if raw.is_empty() {
+                continue;
+            }
+            let glob = glob::Pattern::new(raw)
+                .map_err(|e| format!("Invalid exclude pattern '{}': {}", pattern, e))?;
+            compiled.push((glob, !raw.contains('/')));
+        }
+
+        Ok(PathExcludes { patterns: compiled })
+    }
+
+    fn is_excluded(&self, path: &str) -> bool {
+        if self.patterns.is_empty() {
+            return false;
+        }
+
+        let match_opts = glob::MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+
+        // Check the path itself and every directory containing it
+        let mut prefix = path;
+        loop {
+            let name = prefix.rsplit('/').next().unwrap_or(prefix);
+            let matched = self.patterns.iter().any(|(pattern, name_only)| {
+                let candidate = if *name_only { name } else { prefix };
+                pattern.matches_with(candidate, match_opts)
+            });
+            if matched {
+                return true;
+            }
+
+            match prefix.rfind('/') {
+                Some(idx) => prefix = &prefix[..idx],
+                None => return false,
+            }
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct CommitDetails {
+    id: String,
+    message: String, // full message including the body
+    author: String,
+    author_email: String,
+    date: i64,
+    parents: Vec<String>,
+    co_authors: Vec<String>, // from Co-authored-by trailers, "Name <email>"
+    // Best-effort branch of each parent of a merge, "" where unknown. None for non-merges
+    // or when no parent could be labelled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    merge_parent_branches: Option<Vec<String>>,
+}
+
+#[derive(Serialize)]
+pub struct SignatureStatus {
+    commit_id: String,
+    signed: bool,
+    verified: bool, // Only GPG signatures can be checked, SSH ones are reported unverified
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signer: Option<String>, // User ID of the signing key, when it's in the keyring
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signer_email: Option<String>, // Email part of signer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key_id: Option<String>, // Fingerprint, or the long key ID when the key isn't in the keyring
 }
 
 #[derive(Serialize)]
@@ -25,10 +173,219 @@ pub struct CommitResponse {
     has_more: bool,
 }
 
+// Same data as CommitResponse without repeating strings, for large histories.
+// A parent index below commits.len() points into commits, anything above that into
+// external_parents (parents outside the returned page).
+#[derive(Serialize)]
+pub struct CompactCommitResponse {
+    commits: Vec<CompactCommit>,
+    authors: Vec<String>, // Names and emails of authors and committers
+    external_parents: Vec<String>,
+    has_more: bool,
+}
+
+#[derive(Serialize)]
+pub struct CompactCommit {
+    id: String,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+    author: usize, // Index into authors, like the other identity fields
+    author_email: usize,
+    author_date: i64,
+    committer: usize,
+    committer_email: usize,
+    committer_date: i64,
+    date: i64,
+    parents: Vec<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    refs: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    head_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uncommitted_state: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    is_virtual: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stats: Option<CommitStats>,
+}
+
+#[derive(Serialize)]
+pub struct ThreeWayDiff {
+    path: String,
+    base: Option<String>, // common ancestor, None when both sides added the file
+    ours: Option<String>, // None when deleted on our side
+    theirs: Option<String>, // None when deleted on their side
+}
+
+#[derive(Serialize)]
+pub struct MergeStatus {
+    merging: bool,
+    merge_heads: Vec<String>,     // OIDs from MERGE_HEAD
+    merge_source: Option<String>, // branch name pointing at the first merge head, if any
+    conflicted: Vec<String>,
+    resolved: Vec<String>, // staged paths without conflicts (resolved or merged cleanly)
+}
+
+#[derive(Serialize)]
+pub struct RebaseProgress {
+    rebasing: bool,
+    interactive: bool,
+    step: Option<usize>, // 1-based index of the commit being applied
+    total: Option<usize>,
+    current_commit: Option<String>, // original OID of the commit being applied
+    onto: Option<String>,
+    branch: Option<String>, // branch being rebased, None when rebasing a detached HEAD
+}
+
+#[derive(Serialize)]
+pub struct BranchTracking {
+    branch: String,
+    upstream: Option<String>,             // Short name, e.g. "origin/main"
+    ahead_behind: Option<(usize, usize)>, // None without an upstream
+}
+
+#[derive(Serialize)]
+pub struct RebaseResult {
+    finished: bool,             // All steps were applied and the branch was updated
+    conflicts: Vec<String>,     // Conflicted paths of the step the rebase stopped at
+    stopped_at: Option<String>, // Original OID of that step
+    head: Option<String>,       // HEAD after finishing
+}
+
+#[derive(Serialize)]
+pub struct GitRemote {
+    name: String,
+    url: Option<String>,
+    push_url: Option<String>, // Only set when a separate pushurl is configured
+}
+
+#[derive(Serialize)]
+pub struct HistoryEditability {
+    on_branch: bool, // False for detached HEAD or an unborn branch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    branch: Option<String>,
+    has_upstream: bool,
+    behind_upstream: bool, // The upstream has commits this branch doesn't
+    head_pushed: bool,     // HEAD is already on the upstream, rewriting it needs a force push
+    clean_worktree: bool,  // No uncommitted changes to tracked files
+}
+
+#[derive(Serialize)]
+pub struct DivergenceInfo {
+    branch: String,
+    upstream: String,           // Short name, e.g. "origin/main"
+    merge_base: Option<String>, // None when the histories are unrelated
+    ahead: usize,               // Commits on the branch that the upstream doesn't have
+    behind: usize,              // Commits on the upstream that the branch doesn't have
+}
+
+#[derive(Serialize)]
+pub struct OdbStats {
+    loose_objects: usize,
+    loose_estimated: bool, // True when loose_objects was extrapolated from a sample
+    packfiles: usize,
+    packed_objects: usize,
+    total_objects: usize,
+    gc_recommended: bool, // Same threshold `git gc --auto` uses
+}
+
+#[derive(Serialize)]
+pub struct SwitchResult {
+    stashed: bool,    // Uncommitted changes were stashed before switching
+    restored: bool,   // The stash was re-applied and dropped
+    conflicted: bool, // Re-applying conflicted, the changes are still in the stash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stash_ref: Option<String>, // Where the changes are kept when they couldn't be restored
+}
+
+#[derive(Serialize)]
+pub struct ReflogEntry {
+    old_oid: String,
+    new_oid: String,
+    message: String,   // e.g. "checkout: moving from main to feature"
+    operation: String, // e.g. "checkout", "commit", "amend", "reset", "rebase", or "other"
+    committer: String,
+    date: i64,
+}
+
+#[derive(Serialize)]
+pub struct RefNameValidation {
+    valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>, // Why the name is invalid, suitable for showing next to the input
+}
+
+#[derive(Serialize)]
+pub struct SubmoduleState {
+    name: String,
+    path: String,
+    state: String, // "uninitialized", "out_of_date", "modified" or "up_to_date"
+    recorded_oid: Option<String>, // Commit recorded in HEAD
+    workdir_oid: Option<String>, // Commit checked out in the submodule
+}
+
+#[derive(Serialize, Clone)]
+pub struct FetchProgress {
+    name: String, // What is being fetched, e.g. the submodule name
+    received_objects: usize,
+    total_objects: usize,
+    received_bytes: usize,
+}
+
+#[derive(Serialize)]
+pub struct RefUpdate {
+    #[serde(rename = "ref")]
+    ref_name: String, // e.g. "refs/remotes/origin/main"
+    old_oid: Option<String>, // None for refs the fetch created
+    new_oid: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct LargeBlob {
+    oid: String,
+    size: usize,
+    paths: Vec<String>,
+    commits: Vec<String>, // commits where the blob first appeared at one of its paths
+}
+
+#[derive(Serialize)]
+pub struct BlameLine {
+    line_number: usize, // 1-based line number in the file at the blamed commit
+    content: String,
+    commit_id: String,
+    author: String,
+    date: i64,
+    original_path: String, // Path of the file in `commit_id` (differs from the requested path across renames)
+}
+
+#[derive(Serialize)]
+pub struct AuthorStat {
+    name: String,
+    email: String,
+    count: usize,
+}
+
+#[derive(Serialize)]
+pub struct BucketCount {
+    start: String, // First day of the bucket (YYYY-MM-DD), weeks start on Monday
+    count: usize,
+}
+
+#[derive(Serialize)]
+pub struct SignatureInfo {
+    name: String,
+    email: String,
+}
+
 #[derive(Serialize)]
 pub struct GitRef {
     pub name: String,
     pub commit_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_name: Option<String>, // e.g. "refs/remotes/origin/main"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>, // "branch", "remote", "tag" or "other"
 }
 
 #[derive(Serialize)]
@@ -36,11 +393,137 @@ pub struct FileDiff {
     path: String,
     old_content: String,
     new_content: String,
+    status: String, // "added", "deleted", "modified", "renamed", "copied", ...
+    #[serde(skip_serializing_if = "Option::is_none")]
+    old_path: Option<String>, // Previous path of a renamed or copied file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    old_content_base64: Option<String>, // raw image bytes, only with get_diff(image_previews)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    new_content_base64: Option<String>,
+    binary: bool, // Binary files are sent without text content
+    #[serde(skip_serializing_if = "Option::is_none")]
+    old_size: Option<u64>, // Blob sizes in bytes, only for binary files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    new_size: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct WorkingDiff {
+    staged: Vec<FileDiff>,   // HEAD to index
+    unstaged: Vec<FileDiff>, // Index to working tree, including untracked files
 }
 
 #[derive(Serialize)]
 pub struct DiffResponse {
     files: Vec<FileDiff>,
+    files_truncated: bool, // True when max_files cut off some of the changed files
+    total_files: usize,    // Number of changed files before truncation
+}
+
+#[derive(Serialize)]
+pub struct FileHunks {
+    path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    old_path: Option<String>, // Only set when the file was renamed or copied
+    status: String, // "added", "deleted", "modified", "renamed", ...
+    binary: bool,   // Binary files have no hunks
+    hunks: Vec<DiffHunk>,
+}
+
+#[derive(Serialize)]
+pub struct DiffHunk {
+    header: String, // e.g. "@@ -1,4 +1,5 @@ fn main() {"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    function_context: Option<String>, // The enclosing function from the header, e.g. "fn main() {"
+    old_start: u32,
+    old_lines: u32,
+    new_start: u32,
+    new_lines: u32,
+    lines: Vec<DiffLine>,
+}
+
+#[derive(Serialize)]
+pub struct DiffLine {
+    origin: char,    // '+', '-', ' ', or '=', '>', '<' for end-of-file newline changes
+    content: String, // Without the trailing newline
+    #[serde(skip_serializing_if = "Option::is_none")]
+    old_lineno: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    new_lineno: Option<u32>,
+}
+
+#[derive(Serialize)]
+pub struct StagedPath {
+    path: String,
+    status: String, // "added", "deleted", "modified", "renamed", ...
+}
+
+#[derive(Serialize)]
+pub struct DirSummary {
+    directory: String, // Top-level directory, empty for files at the repository root
+    files_changed: usize,
+    additions: usize,
+    deletions: usize,
+}
+
+// Read-only mode, kept in Tauri state. While enabled, the invoke handler in lib.rs rejects
+// every command in MUTATING_COMMANDS with a "ReadOnly:" error before it runs
+#[derive(Default)]
+pub struct ReadOnlyMode(std::sync::atomic::AtomicBool);
+
+impl ReadOnlyMode {
+    pub fn is_enabled(&self) -> bool {
+        self.0.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    fn set(&self, enabled: bool) {
+        self.0.store(enabled, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+// Commands that modify the repository (refs, index, working tree, stashes, config) or a
+// remote. New mutating commands must be added here to be gated by read-only mode.
+// Commands that only read, or only write outside the repository (materialize_tree and
+// archive_commit refuse targets in the working tree or .git), stay available
+pub const MUTATING_COMMANDS: &[&str] = &[
+    "force_add_path",
+    "stage_file",
+    "unstage_file",
+    "set_repo_description",
+    "resolve_conflict_with",
+    "rebase_continue",
+    "update_submodule",
+    "checkout_commit",
+    "checkout_branch",
+    "switch_branch_autostash",
+    "undo_commits",
+    "reorder_commits",
+    "squash_commits",
+    "drop_commit",
+    "reword_commit",
+    "cherry_pick_range",
+    "fetch_remote",
+    "pull_branch",
+    "fast_forward",
+    "push_branch",
+    "create_branch",
+    "delete_branch",
+    "delete_merged_branches",
+    "create_tag",
+    "delete_tag",
+    "annotate_tag",
+    "move_tag",
+    "commit",
+    "delete_remote_branch",
+    "create_stash",
+    "apply_stash",
+    "drop_stash",
+    "pop_stash",
+];
+
+#[tauri::command]
+pub fn set_read_only(read_only: tauri::State<'_, ReadOnlyMode>, enabled: bool) {
+    read_only.set(enabled);
 }
 
 #[tauri::command]
@@ -64,6 +547,8 @@ pub fn get_all_refs(repo_path: String) -> Result<Vec<GitRef>, String> {
                         refs.push(GitRef {
                             name: short_name,
                             commit_id: target.to_string(),
+                            full_name: None,
+                            kind: None,
                         });
                     }
                 }
@@ -78,6 +563,8 @@ pub fn get_all_refs(repo_path: String) -> Result<Vec<GitRef>, String> {
             refs.push(GitRef {
                 name: format!("stash@{{{}}}", i),
                 commit_id: id.to_string(),
+                full_name: None,
+                kind: None,
             });
         }
     }
@@ -86,44 +573,148 @@ pub fn get_all_refs(repo_path: String) -> Result<Vec<GitRef>, String> {
 }
 
 #[tauri::command]
+pub fn resolve_ref_full(repo_path: String, name: String) -> Result<Vec<GitRef>, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let remotes = repo.remotes().map_err(|e| e.to_string())?;
+
+    // Same lookup order as git's short ref resolution, plus the name on every remote
+    let mut candidates = vec![
+        name.clone(),
+        format!("refs/{}", name),
+        format!("refs/tags/{}", name),
+        format!("refs/heads/{}", name),
+        format!("refs/remotes/{}", name),
+    ];
+    for remote in remotes.iter().flatten() {
+        candidates.push(format!("refs/remotes/{}/{}", remote, name));
+    }
+
+    let mut refs: Vec<GitRef> = Vec::new();
+    for candidate in candidates {
+        // Nothing matching is not an error, the UI just has nothing to choose from
+        let reference = match repo.find_reference(&candidate) {
+            Ok(reference) => reference,
+            Err(_) => continue,
+        };
+        let full_name = match reference.name() {
+            Some(full_name) => full_name.to_string(),
+            None => continue,
+        };
+        if refs
+            .iter()
+            .any(|r| r.full_name.as_deref() == Some(full_name.as_str()))
+        {
+            continue;
+        }
+
+        // Symbolic refs like origin/HEAD and annotated tags are peeled to the commit
+        let commit_id = match reference.peel_to_commit() {
+            Ok(commit) => commit.id(),
+            Err(_) => continue,
+        };
+
+        refs.push(GitRef {
+            name: reference.shorthand().unwrap_or(&full_name).to_string(),
+            commit_id: commit_id.to_string(),
+            full_name: Some(full_name),
+            kind: Some(ref_kind(&reference).to_string()),
+        });
+    }
+
+    Ok(refs)
+}
+
+// All refs whose target peels to `commit_id`, for loading ref labels on demand
+#[tauri::command]
+pub fn refs_at(repo_path: String, commit_id: String) -> Result<Vec<GitRef>, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let oid = Oid::from_str(&commit_id)
+        .map_err(|e| format!("Invalid commit ID '{}': {}", commit_id, e))?;
+
+    let mut refs = Vec::new();
+    for reference in repo.references().map_err(|e| e.to_string())?.flatten() {
+        let Some(full_name) = reference.name().map(|name| name.to_string()) else {
+            continue;
+        };
+        // Symbolic refs like origin/HEAD and annotated tags are peeled to the commit
+        if reference.peel_to_commit().ok().map(|commit| commit.id()) != Some(oid) {
+            continue;
+        }
+
+        refs.push(GitRef {
+            name: reference.shorthand().unwrap_or(&full_name).to_string(),
+            commit_id: commit_id.clone(),
+            full_name: Some(full_name),
+            kind: Some(ref_kind(&reference).to_string()),
+        });
+    }
+
+    Ok(refs)
+}
+
+// Helper function to classify a ref for GitRef.kind
+fn ref_kind(reference: &git2::Reference) -> &'static str {
+    if reference.is_branch() {
+        "branch"
+    } else if reference.is_remote() {
+        "remote"
+    } else if reference.is_tag() {
+        "tag"
+    } else {
+        "other"
+    }
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn get_commits(
     repo_path: String,
     limit: usize,
     skip: Option<usize>,
+    path_filter: Option<String>,
+    ref_filter: Option<Vec<String>>,
+    follow: Option<bool>,
+    exclude_paths: Option<Vec<String>>,
+    parent_summaries: Option<bool>,
+    author_filter: Option<String>,
+    commits_by_committer: Option<bool>,
+    include_stats: Option<bool>,
+    message_filter: Option<String>,
+    since: Option<i64>,
+    until: Option<i64>,
 ) -> Result<CommitResponse, String> {
     let skip = skip.unwrap_or(0);
+    // Treat an empty filter as no filter
+    let mut path_filter = path_filter.filter(|p| !p.is_empty());
+    let follow = follow.unwrap_or(false);
+    // Only affects which refs decorate commits, not which refs seed the walk
+    let ref_filter = RefFilter::parse(ref_filter.unwrap_or_default())?;
+    // Only affects whether the working-copy node is shown
+    let exclude_paths = PathExcludes::parse(exclude_paths.unwrap_or_default())?;
+    let author_filter = author_filter
+        .filter(|a| !a.is_empty())
+        .map(|a| a.to_lowercase());
+    // Filter on who landed the commit instead of who wrote it
+    let commits_by_committer = commits_by_committer.unwrap_or(false);
+    let message_filter = message_filter
+        .filter(|m| !m.is_empty())
+        .map(|m| m.to_lowercase());
+    // Opt-in since it diffs every returned commit against its parent
+    let include_stats = include_stats.unwrap_or(false);
     let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    // Pathspecs are relative to the working tree, so a path picked in a file dialog
+    // (absolute, or "./src") has to be converted first. Bare repos have nothing to resolve against.
+    if repo.workdir().is_some() {
+        path_filter = path_filter
+            .map(|path| workdir_relative_path(&repo, &path).map(|p| pathspec_string(&p)))
+            .transpose()?;
+    }
     let mut walk = repo.revwalk().map_err(|e| e.to_string())?;
 
     walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
         .map_err(|e| e.to_string())?;
 
-    // Instead of only pushing HEAD, push all references to ensure we get complete history
-    // This ensures that even in detached HEAD state, we can see the full commit graph
-
-    // Push HEAD if it exists
-    if let Ok(head) = repo.head() {
-        if let Some(target) = head.target() {
-            walk.push(target).map_err(|e| e.to_string())?;
-        }
-    }
-
-    // Push all local branches and tags
-    if let Ok(refs) = repo.references() {
-        for r in refs {
-            if let Ok(r) = r {
-                if let Some(name) = r.name() {
-                    // Only include local branches and tags, not remote tracking branches
-                    if name.starts_with("refs/heads/") || name.starts_with("refs/tags/") {
-                        if let Some(target) = r.target() {
-                            // Ignore errors when pushing (some refs might be invalid)
-                            let _ = walk.push(target);
-                        }
-                    }
-                }
-            }
-        }
-    }
+    push_history_roots(&repo, &mut walk)?;
 
     // Handle Stashes
     let mut stash_map = HashMap::new();
@@ -161,45 +752,39 @@ pub fn get_commits(
 
     let mut commits = Vec::new();
 
-    // Determine HEAD type by reading .git/HEAD file directly
-    let head_type = {
-        let git_dir = repo.path();
-        let head_file = git_dir.join("HEAD");
-
-        if let Ok(content) = fs::read_to_string(&head_file) {
-            println!("DEBUG: HEAD file content: {:?}", content.trim());
-            if content.starts_with("ref: ") {
-                // Symbolic reference (branch HEAD)
-                println!("DEBUG: Detected as BRANCH HEAD from HEAD file");
-                Some("branch".to_string())
-            } else {
-                // Direct reference (detached HEAD)
-                println!("DEBUG: Detected as DETACHED HEAD from HEAD file");
-                Some("detached".to_string())
-            }
-        } else {
-            println!("DEBUG: Could not read HEAD file");
-            // Fallback to git2 method
-            match repo.head() {
-                Ok(head) => {
-                    if head.symbolic_target().is_some() {
-                        Some("branch".to_string())
-                    } else {
-                        Some("detached".to_string())
-                    }
-                }
-                Err(_) => None,
-            }
-        }
-    };
+    let head_type = detect_head_type(&repo);
 
     // Get HEAD target OID for comparison
     let head_target_oid = repo.head().ok().and_then(|h| h.target());
 
-    // Check for uncommitted changes (only for first page)
-    if skip == 0 {
+    // Check for uncommitted changes (only for first page). The node is stamped with the
+    // current time, so a date range that excludes now hides it too
+    let now = chrono::Utc::now().timestamp();
+    // The node is authored and committed by the configured user, and its message is fixed
+    let user = repo.signature().ok();
+    let user_name = user
+        .as_ref()
+        .and_then(|signature| signature.name().map(|name| name.to_string()))
+        .unwrap_or_default();
+    let user_email = user
+        .as_ref()
+        .and_then(|signature| signature.email().map(|email| email.to_string()))
+        .unwrap_or_default();
+    let user_matches = author_filter.as_ref().is_none_or(|filter| {
+        [&user_name, &user_email]
+            .iter()
+            .any(|field| field.to_lowercase().contains(filter.as_str()))
+    });
+    let message_matches = message_filter
+        .as_ref()
+        .is_none_or(|filter| "uncommitted changes".contains(filter.as_str()));
+    if skip == 0 && in_date_range(now, since, until) && user_matches && message_matches {
         let mut status_opts = StatusOptions::new();
         status_opts.include_untracked(true);
+        // Only show the working-copy node if it touches the filtered path
+        if let Some(path) = &path_filter {
+            status_opts.pathspec(path);
+        }
 
         if let Ok(statuses) = repo.statuses(Some(&mut status_opts)) {
             if !statuses.is_empty() {
@@ -208,6 +793,12 @@ pub fn get_commits(
                 let mut has_unstaged = false;
 
                 for entry in statuses.iter() {
+                    if entry
+                        .path()
+                        .is_some_and(|path| exclude_paths.is_excluded(path))
+                    {
+                        continue;
+                    }
                     let status = entry.status();
                     // Check for staged changes (INDEX_* flags)
                     if status.intersects(Status::INDEX_NEW | Status::INDEX_MODIFIED | Status::INDEX_DELETED | Status::INDEX_RENAMED | Status::INDEX_TYPECHANGE) {
@@ -228,7 +819,7 @@ pub fn get_commits(
                     (true, false) => Some("staged".to_string()),
                     (false, true) => Some("unstaged".to_string()),
                     (true, true) => Some("mixed".to_string()),
-                    (false, false) => None, // Should not happen if statuses is not empty
+                    (false, false) => None, // Only excluded paths changed
                 };
 
                 // Create a virtual "Uncommitted Changes" commit
@@ -239,79 +830,43 @@ pub fn get_commits(
                     .and_then(|h| h.target())
                     .map(|oid| oid.to_string());
 
-                if let Some(parent_id) = head_oid {
+                if let (Some(parent_id), Some(_)) = (head_oid, &uncommitted_state) {
                     commits.push(GitCommit {
-                        id: "working-copy".to_string(),
+                        id: WORKING_COPY_ID.to_string(),
                         message: "Uncommitted Changes".to_string(),
+                        body: None,
                         author: "You".to_string(),
-                        date: chrono::Utc::now().timestamp(),
+                        author_email: user_email.clone(),
+                        author_date: now,
+                        committer: "You".to_string(),
+                        committer_email: user_email,
+                        committer_date: now,
+                        date: now,
                         parents: vec![parent_id],
                         refs: vec![],
                         head_type: None,
                         uncommitted_state,
+                        is_virtual: true,
+                        parent_summaries: None,
+                        stats: working_copy_stats(&repo, path_filter.as_deref(), &exclude_paths),
                     });
                 }
             }
         }
     }
 
-    // Skip commits
-    let mut walk_iter = walk.skip(skip);
+    // Skip and limit are applied to the commits that are actually returned
+    // (after filtering), so the frontend can use the number of loaded commits as skip
+    let mut skipped = 0;
     let mut count = 0;
+    let mut has_more = false;
 
-    while count < limit {
-        let oid = match walk_iter.next() {
-            Some(Ok(oid)) => oid,
-            Some(Err(e)) => return Err(e.to_string()),
-            None => break,
-        };
+    for oid in walk {
+        let oid = oid.map_err(|e| e.to_string())?;
 
         let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
 
         let message = commit.summary().unwrap_or("").to_string();
-        let author = commit.author().name().unwrap_or("").to_string();
-        let date = commit.time().seconds();
-        let parents = commit.parent_ids().map(|p| p.to_string()).collect();
-
-        // Collect refs
-        let mut refs = Vec::new();
-
-        // Check if HEAD points to this commit
-        if let Some(target_oid) = head_target_oid {
-            if target_oid == oid {
-                refs.push("HEAD".to_string());
-            }
-        }
-
-        // Get other refs
-        if let Ok(repo_refs) = repo.references() {
-            for r in repo_refs {
-                if let Ok(r) = r {
-                    if r.target() == Some(oid) {
-                        if let Some(name) = r.name() {
-                            // Skip HEAD if we already added it (though repo.references usually doesn't show HEAD if symbolic)
-                            if name == "HEAD" {
-                                continue;
-                            }
-
-                            if name.starts_with("refs/heads/") {
-                                refs.push(name.replace("refs/heads/", ""));
-                            } else if name.starts_with("refs/remotes/") {
-                                refs.push(name.replace("refs/remotes/", ""));
-                            } else {
-                                // Keep refs/tags/ and others as is for now, frontend handles tags
-                                refs.push(name.to_string());
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        // Check if this commit is a stash
-        if let Some(stash_name) = stash_map.get(&oid) {
-            refs.push(stash_name.clone());
-        }
 
         // Check if this commit is a stash artifact we want to hide
         // Stash artifacts usually have messages like "index on ..." or "untracked files on ..."
@@ -339,302 +894,4826 @@ pub fn get_commits(
             continue;
         }
 
+        // Commit time, like `git log --since/--until`
+        let time = commit.time().seconds();
+        if since.is_some_and(|since| time < since) {
+            // The walk is time sorted, so everything after this is older still.
+            // git log --since stops early the same way, clock skew aside.
+            break;
+        }
+        if !in_date_range(time, since, until) {
+            continue;
+        }
+
+        // Equivalent of `git log -- <path>`: only keep commits that touched the path
+        if let Some(path) = &path_filter {
+            if !commit_touches_path(&repo, &commit, path)? {
+                continue;
+            }
+
+            // Equivalent of `git log --follow`: keep tracking the file under its previous name
+            if follow {
+                if let Some(old_path) = renamed_from(&repo, &commit, path)? {
+                    path_filter = Some(old_path);
+                }
+            }
+        }
+
+        // Case-insensitive substring match on the name or email
+        if let Some(filter) = &author_filter {
+            let signature = if commits_by_committer {
+                commit.committer()
+            } else {
+                commit.author()
+            };
+            let matches = [signature.name(), signature.email()]
+                .into_iter()
+                .flatten()
+                .any(|field| field.to_lowercase().contains(filter.as_str()));
+            if !matches {
+                continue;
+            }
+        }
+
+        // Case-insensitive substring match on the summary line
+        if let Some(filter) = &message_filter {
+            if !message.to_lowercase().contains(filter.as_str()) {
+                continue;
+            }
+        }
+
+        if skipped < skip {
+            skipped += 1;
+            continue;
+        }
+
+        if count == limit {
+            // There is at least one more matching commit beyond this page
+            has_more = true;
+            break;
+        }
+
+        let author = commit.author();
+        let committer = commit.committer();
+        let date = commit.time().seconds();
+        let parents = commit.parent_ids().map(|p| p.to_string()).collect();
+
+        let is_head = head_target_oid == Some(oid);
+        let refs = commit_refs(&repo, oid, is_head, &stash_map, &ref_filter);
+
         // Set head_type only for the commit that HEAD points to
-        let commit_head_type = if refs.contains(&"HEAD".to_string()) {
-            head_type.clone()
-        } else {
-            None
-        };
+        let commit_head_type = if is_head { head_type.clone() } else { None };
 
         commits.push(GitCommit {
             id: oid.to_string(),
             message,
-            author,
+            body: commit.body().map(|body| body.to_string()),
+            author: author.name().unwrap_or("").to_string(),
+            author_email: author.email().unwrap_or("").to_string(),
+            author_date: author.when().seconds(),
+            committer: committer.name().unwrap_or("").to_string(),
+            committer_email: committer.email().unwrap_or("").to_string(),
+            committer_date: committer.when().seconds(),
             date,
             parents,
             refs,
             head_type: commit_head_type,
             uncommitted_state: None,
+            is_virtual: false,
+            parent_summaries: None,
+            stats: if include_stats {
+                commit_stats(&repo, &commit)
+            } else {
+                None
+            },
         });
 
         count += 1;
     }
 
-    // Check if there are more commits
-    let has_more = walk_iter.next().is_some();
+    // Opt-in to keep the payload small. Parents outside this page are left out, the
+    // frontend already has the ones from earlier pages
+    if parent_summaries.unwrap_or(false) {
+        let summaries: HashMap<String, String> = commits
+            .iter()
+            .filter(|c| !c.is_virtual)
+            .map(|c| (c.id.clone(), c.message.clone()))
+            .collect();
+        for commit in &mut commits {
+            let parents = commit
+                .parents
+                .iter()
+                .filter_map(|oid| {
+                    summaries.get(oid).map(|summary| ParentSummary {
+                        oid: oid.clone(),
+                        summary: summary.clone(),
+                    })
+                })
+                .collect();
+            commit.parent_summaries = Some(parents);
+        }
+    }
 
     Ok(CommitResponse { commits, has_more })
 }
 
+// get_commits in the CompactCommitResponse format, the verbose one stays the default.
+// Parent summaries aren't included since each parent's message is already in the page.
 #[tauri::command]
-pub fn get_diff(
+#[allow(clippy::too_many_arguments)]
+pub fn get_commits_compact(
     repo_path: String,
-    old_commit: String,
-    new_commit: String,
-) -> Result<DiffResponse, String> {
-    println!("get_diff called: old={}, new={}", old_commit, new_commit);
+    limit: usize,
+    skip: Option<usize>,
+    path_filter: Option<String>,
+    ref_filter: Option<Vec<String>>,
+    follow: Option<bool>,
+    exclude_paths: Option<Vec<String>>,
+    author_filter: Option<String>,
+    commits_by_committer: Option<bool>,
+    include_stats: Option<bool>,
+    message_filter: Option<String>,
+    since: Option<i64>,
+    until: Option<i64>,
+) -> Result<CompactCommitResponse, String> {
+    let response = get_commits(
+        repo_path,
+        limit,
+        skip,
+        path_filter,
+        ref_filter,
+        follow,
+        exclude_paths,
+        None,
+        author_filter,
+        commits_by_committer,
+        include_stats,
+        message_filter,
+        since,
+        until,
+    )?;
 
-    // Validate commit IDs
-    if old_commit == "working-copy" || new_commit == "working-copy" {
-        return Err("Cannot diff with working-copy. Please select real commits.".to_string());
+    let row_of: HashMap<String, usize> = response
+        .commits
+        .iter()
+        .enumerate()
+        .map(|(row, commit)| (commit.id.clone(), row))
+        .collect();
+    let mut authors = Vec::new();
+    let mut author_index: HashMap<String, usize> = HashMap::new();
+    let mut intern = |value: String| {
+        *author_index.entry(value).or_insert_with_key(|value| {
+            authors.push(value.clone());
+            authors.len() - 1
+        })
+    };
+    let mut external_parents = Vec::new();
+    let mut external_index: HashMap<String, usize> = HashMap::new();
+
+    let commits = response
+        .commits
+        .into_iter()
+        .map(|commit| {
+            let parents = commit
+                .parents
+                .into_iter()
+                .map(|parent| match row_of.get(&parent) {
+                    Some(&row) => row,
+                    None => {
+                        let index = *external_index.entry(parent).or_insert_with_key(|oid| {
+                            external_parents.push(oid.clone());
+                            external_parents.len() - 1
+                        });
+                        row_of.len() + index
+                    }
+                })
+                .collect();
+
+            CompactCommit {
+                id: commit.id,
+                message: commit.message,
+                body: commit.body,
+                author: intern(commit.author),
+                author_email: intern(commit.author_email),
+                author_date: commit.author_date,
+                committer: intern(commit.committer),
+                committer_email: intern(commit.committer_email),
+                committer_date: commit.committer_date,
+                date: commit.date,
+                parents,
+                refs: commit.refs,
+                head_type: commit.head_type,
+                uncommitted_state: commit.uncommitted_state,
+                is_virtual: commit.is_virtual,
+                stats: commit.stats,
+            }
+        })
+        .collect();
+
+    Ok(CompactCommitResponse {
+        commits,
+        authors,
+        external_parents,
+        has_more: response.has_more,
+    })
+}
+
+// Like get_commits, but only walks `commit_id` and its ancestors
+#[tauri::command]
+pub fn get_ancestry(
+    repo_path: String,
+    commit_id: String,
+    limit: usize,
+    skip: Option<usize>,
+) -> Result<CommitResponse, String> {
+    let skip = skip.unwrap_or(0);
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    let oid = Oid::from_str(&commit_id)
+        .map_err(|e| format!("Invalid commit ID '{}': {}", commit_id, e))?;
+    repo.find_commit(oid).map_err(|e| e.to_string())?;
+
+    let mut walk = repo.revwalk().map_err(|e| e.to_string())?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
+        .map_err(|e| e.to_string())?;
+    walk.push(oid).map_err(|e| e.to_string())?;
+
+    let head_type = detect_head_type(&repo);
+    let head_target_oid = repo.head().ok().and_then(|h| h.target());
+    // Stash commits are never ancestors of another commit, so there are none to decorate
+    let stash_map = HashMap::new();
+    let ref_filter = RefFilter::parse(vec![])?;
+
+    let mut commits = Vec::new();
+    let mut has_more = false;
+    for (idx, oid) in walk.skip(skip).enumerate() {
+        if idx == limit {
+            has_more = true;
+            break;
+        }
+
+        let oid = oid.map_err(|e| e.to_string())?;
+        let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+        let is_head = head_target_oid == Some(oid);
+
+        commits.push(GitCommit {
+            refs: commit_refs(&repo, oid, is_head, &stash_map, &ref_filter),
+            head_type: if is_head { head_type.clone() } else { None },
+            ..undecorated_commit(&commit)
+        });
     }
 
+    Ok(CommitResponse { commits, has_more })
+}
+
+// The commits strictly between two loaded commits on the child's first-parent line, child side
+// first. Lets the UI draw a continuous line when paging left the middle of the chain unloaded.
+#[tauri::command]
+pub fn get_connecting_commits(
+    repo_path: String,
+    child_oid: String,
+    ancestor_oid: String,
+    max: usize,
+) -> Result<Vec<GitCommit>, String> {
     let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
 
-    let old_oid = Oid::from_str(&old_commit)
-        .map_err(|e| format!("Invalid commit ID '{}': {}", old_commit, e))?;
-    let new_oid = Oid::from_str(&new_commit)
-        .map_err(|e| format!("Invalid commit ID '{}': {}", new_commit, e))?;
+    let child = Oid::from_str(&child_oid)
+        .map_err(|e| format!("Invalid commit ID '{}': {}", child_oid, e))?;
+    let ancestor = Oid::from_str(&ancestor_oid)
+        .map_err(|e| format!("Invalid commit ID '{}': {}", ancestor_oid, e))?;
+    let mut commit = repo.find_commit(child).map_err(|e| e.to_string())?;
+    if child == ancestor {
+        return Ok(vec![]);
+    }
 
-    let old_commit_obj = repo.find_commit(old_oid).map_err(|e| e.to_string())?;
-    let new_commit_obj = repo.find_commit(new_oid).map_err(|e| e.to_string())?;
+    let head_type = detect_head_type(&repo);
+    let head_target_oid = repo.head().ok().and_then(|h| h.target());
+    // Stash commits are never on a first-parent line, so there are none to decorate
+    let stash_map = HashMap::new();
+    let ref_filter = RefFilter::parse(vec![])?;
+
+    let mut commits = Vec::new();
+    loop {
+        let parent = commit.parent(0).map_err(|_| {
+            format!(
+                "NotFound: '{}' is not on the first-parent line of '{}'",
+                ancestor_oid, child_oid
+            )
+        })?;
+        if parent.id() == ancestor {
+            return Ok(commits);
+        }
+        if commits.len() == max {
+            return Err(format!(
+                "NotFound: '{}' is not within {} first-parent commits of '{}'",
+                ancestor_oid, max, child_oid
+            ));
+        }
+
+        let oid = parent.id();
+        let is_head = head_target_oid == Some(oid);
+        commits.push(GitCommit {
+            refs: commit_refs(&repo, oid, is_head, &stash_map, &ref_filter),
+            head_type: if is_head { head_type.clone() } else { None },
+            ..undecorated_commit(&parent)
+        });
+        commit = parent;
+    }
+}
+
+// Commits whose message, author or OID prefix matches `query` (case-insensitive), in graph order
+#[tauri::command]
+pub fn search_commits(
+    repo_path: String,
+    query: String,
+    limit: usize,
+) -> Result<Vec<GitCommit>, String> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Ok(vec![]);
+    }
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    // Same roots (minus stashes) and order as get_commits, so results line up with the graph
+    let mut walk = repo.revwalk().map_err(|e| e.to_string())?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
+        .map_err(|e| e.to_string())?;
+    push_history_roots(&repo, &mut walk)?;
+
+    let head_type = detect_head_type(&repo);
+    let head_target_oid = repo.head().ok().and_then(|h| h.target());
+    let stash_map = HashMap::new();
+    let ref_filter = RefFilter::parse(vec![])?;
+
+    let mut commits = Vec::new();
+    for oid in walk {
+        if commits.len() == limit {
+            break;
+        }
+        let oid = oid.map_err(|e| e.to_string())?;
+        let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+
+        let author = commit.author();
+        let matches = oid.to_string().starts_with(&query)
+            || [commit.message(), author.name(), author.email()]
+                .into_iter()
+                .flatten()
+                .any(|field| field.to_lowercase().contains(&query));
+        if !matches {
+            continue;
+        }
+
+        let is_head = head_target_oid == Some(oid);
+        commits.push(GitCommit {
+            refs: commit_refs(&repo, oid, is_head, &stash_map, &ref_filter),
+            head_type: if is_head { head_type.clone() } else { None },
+            ..undecorated_commit(&commit)
+        });
+    }
+
+    Ok(commits)
+}
+
+// Helper function to determine whether HEAD is on a branch ("branch") or detached ("detached"),
+// reading .git/HEAD directly
+fn detect_head_type(repo: &Repository) -> Option<String> {
+    let git_dir = repo.path();
+    let head_file = git_dir.join("HEAD");
+
+    if let Ok(content) = fs::read_to_string(&head_file) {
+        log::debug!("HEAD file content: {:?}", content.trim());
+        if content.starts_with("ref: ") {
+            // Symbolic reference (branch HEAD)
+            log::debug!("Detected as BRANCH HEAD from HEAD file");
+            Some("branch".to_string())
+        } else {
+            // Direct reference (detached HEAD)
+            log::debug!("Detected as DETACHED HEAD from HEAD file");
+            Some("detached".to_string())
+        }
+    } else {
+        log::debug!("Could not read HEAD file");
+        // Fallback to git2 method
+        match repo.head() {
+            Ok(head) => {
+                if head.symbolic_target().is_some() {
+                    Some("branch".to_string())
+                } else {
+                    Some("detached".to_string())
+                }
+            }
+            Err(_) => None,
+        }
+    }
+}
+
+// Helper function to collect the ref names decorating a commit in the graph
+fn commit_refs(
+    repo: &Repository,
+    oid: Oid,
+    is_head: bool,
+    stash_map: &HashMap<Oid, String>,
+    ref_filter: &RefFilter,
+) -> Vec<String> {
+    let mut refs = Vec::new();
+
+    // Check if HEAD points to this commit
+    if is_head && ref_filter.is_visible("HEAD", "HEAD") {
+        refs.push("HEAD".to_string());
+    }
+
+    // Get other refs
+    if let Ok(repo_refs) = repo.references() {
+        for r in repo_refs.flatten() {
+            if r.target() == Some(oid) {
+                if let Some(name) = r.name() {
+                    // Skip HEAD if we already added it (though repo.references usually doesn't show HEAD if symbolic)
+                    if name == "HEAD" {
+                        continue;
+                    }
+
+                    let short_name = if name.starts_with("refs/heads/") {
+                        name.replace("refs/heads/", "")
+                    } else if name.starts_with("refs/remotes/") {
+                        name.replace("refs/remotes/", "")
+                    } else {
+                        // Keep refs/tags/ and others as is for now, frontend handles tags
+                        name.to_string()
+                    };
+
+                    if ref_filter.is_visible(name, &short_name) {
+                        refs.push(short_name);
+                    }
+                }
+            }
+        }
+    }
+
+    // Check if this commit is a stash
+    if let Some(stash_name) = stash_map.get(&oid) {
+        if ref_filter.is_visible("refs/stash", stash_name) {
+            refs.push(stash_name.clone());
+        }
+    }
+
+    refs
+}
+
+#[tauri::command]
+pub fn get_commit_details(repo_path: String, commit_id: String) -> Result<CommitDetails, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    let oid = Oid::from_str(&commit_id)
+        .map_err(|e| format!("Invalid commit ID '{}': {}", commit_id, e))?;
+    let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+
+    let message = commit.message().unwrap_or("").to_string();
+    let author = commit.author();
+
+    Ok(CommitDetails {
+        id: oid.to_string(),
+        co_authors: parse_co_authors(&message),
+        merge_parent_branches: merge_parent_branches(&repo, &commit),
+        message,
+        author: author.name().unwrap_or("").to_string(),
+        author_email: author.email().unwrap_or("").to_string(),
+        date: commit.time().seconds(),
+        parents: commit.parent_ids().map(|p| p.to_string()).collect(),
+    })
+}
+
+// Signature state of each commit. Kept out of get_commits because verifying runs gpg per commit.
+#[tauri::command]
+pub fn get_signatures(
+    repo_path: String,
+    commit_ids: Vec<String>,
+) -> Result<Vec<SignatureStatus>, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    // Same program git uses for verify-commit
+    let gpg_program = repo
+        .config()
+        .and_then(|config| config.get_string("gpg.program"))
+        .unwrap_or_else(|_| "gpg".to_string());
+
+    let mut statuses = Vec::with_capacity(commit_ids.len());
+    for commit_id in commit_ids {
+        let oid = Oid::from_str(&commit_id)
+            .map_err(|e| format!("Invalid commit ID '{}': {}", commit_id, e))?;
+
+        let mut status = SignatureStatus {
+            commit_id,
+            signed: false,
+            verified: false,
+            signer: None,
+            signer_email: None,
+            key_id: None,
+        };
+        match repo.extract_signature(&oid, None) {
+            Ok((signature, signed_data)) => {
+                status.signed = true;
+                let signature = String::from_utf8_lossy(&signature).to_string();
+                if signature.starts_with("-----BEGIN PGP SIGNATURE-----") {
+                    verify_gpg_signature(&gpg_program, &signature, &signed_data, &mut status);
+                }
+            }
+            // NotFound means the commit simply isn't signed
+            Err(e) if e.code() == git2::ErrorCode::NotFound => {}
+            Err(e) => return Err(e.to_string()),
+        }
+
+        statuses.push(status);
+    }
+
+    Ok(statuses)
+}
+
+// Helper function to create a file with a random name in the temp dir. create_new refuses
+// existing paths, symlinks included, so a file planted by another user is never reused.
+fn create_temp_file() -> std::io::Result<(std::path::PathBuf, fs::File)> {
+    use std::hash::{BuildHasher, Hasher};
+
+    let mut attempts = 0;
+    loop {
+        let random = std::collections::hash_map::RandomState::new()
+            .build_hasher()
+            .finish();
+        let path = std::env::temp_dir().join(format!("super-git-graph-{:016x}", random));
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(file) => return Ok((path, file)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists && attempts < 10 => {
+                attempts += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+// Helper function to check a detached GPG signature with `gpg --verify`, filling in whether it's
+// a good signature and whatever gpg knows about the key. Any failure to run gpg counts as unverified.
+fn verify_gpg_signature(
+    gpg_program: &str,
+    signature: &str,
+    signed_data: &[u8],
+    result: &mut SignatureStatus,
+) {
+    use std::process::{Command, Stdio};
+
+    // gpg only reads one input from stdin, so the signature goes through a file
+    let sig_path = match create_temp_file() {
+        Ok((path, mut file)) => {
+            if file.write_all(signature.as_bytes()).is_err() {
+                let _ = fs::remove_file(&path);
+                return;
+            }
+            path
+        }
+        Err(e) => {
+            log::error!("Failed to create a temporary signature file: {}", e);
+            return;
+        }
+    };
+
+    let output = Command::new(gpg_program)
+        .args(["--status-fd=1", "--verify"])
+        .arg(&sig_path)
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .and_then(|mut child| {
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(signed_data)?;
+            }
+            child.wait_with_output()
+        });
+    let _ = fs::remove_file(&sig_path);
+
+    let status = match output {
+        Ok(output) => String::from_utf8_lossy(&output.stdout).to_string(),
+        Err(e) => {
+            log::error!("Failed to run {}: {}", gpg_program, e);
+            return;
+        }
+    };
+
+    // Status lines look like "[GNUPG:] GOODSIG <long key id> <user id>"
+    for line in status.lines() {
+        let Some(rest) = line.strip_prefix("[GNUPG:] ") else {
+            continue;
+        };
+        let mut fields = rest.splitn(3, ' ');
+        let (keyword, key, user_id) = (fields.next(), fields.next(), fields.next());
+        match keyword {
+            Some("GOODSIG") => result.verified = true,
+            // ERRSIG (e.g. the key isn't in the keyring) only tells which key signed
+            Some("ERRSIG") => {
+                if result.key_id.is_none() {
+                    result.key_id = key.map(|k| k.to_string());
+                }
+                continue;
+            }
+            // The full fingerprint, only sent when the key is known
+            Some("VALIDSIG") => {
+                result.key_id = key.map(|k| k.to_string());
+                continue;
+            }
+            // Signatures made by an expired or revoked key, still with a known signer
+            Some("EXPSIG" | "EXPKEYSIG" | "REVKEYSIG") => {}
+            // A bad signature's claimed identity isn't worth showing
+            _ => continue,
+        }
+        if result.key_id.is_none() {
+            result.key_id = key.map(|k| k.to_string());
+        }
+        if let Some(user_id) = user_id {
+            result.signer_email = user_id
+                .split_once('<')
+                .and_then(|(_, rest)| rest.split_once('>'))
+                .map(|(email, _)| email.to_string());
+            result.signer = Some(user_id.to_string());
+        }
+    }
+}
+
+#[tauri::command]
+pub fn blame_range(
+    repo_path: String,
+    file_path: String,
+    start_line: usize,
+    end_line: usize,
+    commit_id: String,
+    follow: Option<bool>,
+) -> Result<Vec<BlameLine>, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    let oid = Oid::from_str(&commit_id)
+        .map_err(|e| format!("Invalid commit ID '{}': {}", commit_id, e))?;
+    let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+    let tree = commit.tree().map_err(|e| e.to_string())?;
+
+    let entry = tree
+        .get_path(std::path::Path::new(&file_path))
+        .map_err(|_| format!("'{}' does not exist at commit {}", file_path, short_id(oid)))?;
+    let content = read_blob_string(&repo, entry.id())?;
+    let lines: Vec<&str> = content.lines().collect();
+
+    if start_line == 0 || start_line > end_line {
+        return Err(format!(
+            "Invalid line range {}-{}: lines are 1-based and start must not exceed end",
+            start_line, end_line
+        ));
+    }
+    if end_line > lines.len() {
+        return Err(format!(
+            "Line range {}-{} is outside the file, which has {} lines",
+            start_line,
+            end_line,
+            lines.len()
+        ));
+    }
+
+    // Restricting the blame to the range is much cheaper than blaming the whole file
+    let mut blame_opts = git2::BlameOptions::new();
+    blame_opts
+        .newest_commit(oid)
+        .min_line(start_line)
+        .max_line(end_line);
+    // libgit2 follows whole-file renames on its own, so without `follow` stop at the commit
+    // that renamed the file and attribute the older lines to it
+    if !follow.unwrap_or(false) {
+        if let Some(rename_oid) = path_rename_commit(&repo, &commit, &file_path)? {
+            blame_opts.oldest_commit(rename_oid);
+        }
+    }
+    let blame = repo
+        .blame_file(std::path::Path::new(&file_path), Some(&mut blame_opts))
+        .map_err(|e| format!("Failed to blame '{}': {}", file_path, e))?;
+
+    let mut result = Vec::with_capacity(end_line - start_line + 1);
+    for line_number in start_line..=end_line {
+        let hunk = blame
+            .get_line(line_number)
+            .ok_or_else(|| format!("No blame information for line {}", line_number))?;
+        let signature = hunk.final_signature();
+
+        result.push(BlameLine {
+            line_number,
+            content: lines[line_number - 1].to_string(),
+            commit_id: hunk.final_commit_id().to_string(),
+            author: signature.name().unwrap_or("").to_string(),
+            date: signature.when().seconds(),
+            original_path: hunk
+                .path()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_else(|| file_path.clone()),
+        });
+    }
+
+    Ok(result)
+}
+
+// Helper function to find the first-parent commit that gave `path` its current name by
+// renaming it, if the file was not added under that name
+fn path_rename_commit(
+    repo: &Repository,
+    commit: &git2::Commit,
+    path: &str,
+) -> Result<Option<Oid>, String> {
+    let mut current = commit.clone();
+    loop {
+        let parent = match current.parent(0) {
+            Ok(parent) => parent,
+            Err(_) => return Ok(None),
+        };
+        let parent_tree = parent.tree().map_err(|e| e.to_string())?;
+        if parent_tree.get_path(std::path::Path::new(path)).is_err() {
+            return Ok(renamed_from(repo, &current, path)?.map(|_| current.id()));
+        }
+        current = parent;
+    }
+}
+
+// Helper function to extract "Name <email>" values from Co-authored-by trailers.
+// Trailers that don't look like an identity are ignored.
+fn parse_co_authors(message: &str) -> Vec<String> {
+    let Ok(trailers) = git2::message_trailers_strs(message) else {
+        return vec![];
+    };
+
+    trailers
+        .iter()
+        .filter(|(key, _)| key.eq_ignore_ascii_case("Co-authored-by"))
+        .map(|(_, value)| value.trim())
+        .filter(|value| {
+            let Some((name, rest)) = value.split_once('<') else {
+                return false;
+            };
+            !name.trim().is_empty() && rest.ends_with('>') && rest.len() > 1
+        })
+        .map(|value| value.to_string())
+        .collect()
+}
+
+// Helper function to guess which branch each parent of a merge commit came from.
+// The merge message is the most reliable source since it names the branches as they were
+// at merge time. Otherwise a parent is matched to a branch still pointing at it, and the
+// first parent to a branch that contains the merge.
+fn merge_parent_branches(repo: &Repository, commit: &git2::Commit) -> Option<Vec<String>> {
+    if commit.parent_count() < 2 {
+        return None;
+    }
+    let (merged, into) = parse_merge_summary(commit.summary().unwrap_or(""));
+
+    // Local branches first, so "main" wins over "origin/main"
+    let mut branches = Vec::new();
+    for branch_type in [BranchType::Local, BranchType::Remote] {
+        if let Ok(iter) = repo.branches(Some(branch_type)) {
+            for (branch, _) in iter.flatten() {
+                if let (Ok(Some(name)), Some(tip)) = (branch.name(), branch.get().target()) {
+                    branches.push((name.to_string(), tip));
+                }
+            }
+        }
+    }
+    let branch_at = |oid: Oid| {
+        branches
+            .iter()
+            .find(|(_, tip)| *tip == oid)
+            .map(|(name, _)| name.clone())
+    };
+
+    let labels: Vec<String> = commit
+        .parent_ids()
+        .enumerate()
+        .map(|(i, parent)| {
+            let label = match i {
+                0 => into.clone().or_else(|| {
+                    branches
+                        .iter()
+                        .find(|(_, tip)| {
+                            *tip == commit.id()
+                                || repo.graph_descendant_of(*tip, commit.id()).unwrap_or(false)
+                        })
+                        .map(|(name, _)| name.clone())
+                }),
+                1 => merged.clone().or_else(|| branch_at(parent)),
+                _ => branch_at(parent),
+            };
+            label.unwrap_or_default()
+        })
+        .collect();
+
+    labels
+        .iter()
+        .any(|label| !label.is_empty())
+        .then_some(labels)
+}
+
+// Helper function to read the branch names out of git's default merge summaries:
+// "Merge branch 'feature' into main", "Merge remote-tracking branch 'origin/feature'"
+// and "Merge pull request #12 from owner/feature". Returns (merged branch, target branch).
+fn parse_merge_summary(summary: &str) -> (Option<String>, Option<String>) {
+    if let Some(rest) = summary.strip_prefix("Merge pull request #") {
+        // The source is "owner/branch", where the branch itself may contain slashes
+        let merged = rest
+            .split_once(" from ")
+            .and_then(|(_, source)| source.split_whitespace().next())
+            .and_then(|source| source.split_once('/'))
+            .map(|(_, branch)| branch.to_string());
+        return (merged, None);
+    }
+
+    let rest = match summary
+        .strip_prefix("Merge branch '")
+        .or_else(|| summary.strip_prefix("Merge remote-tracking branch '"))
+    {
+        Some(rest) => rest,
+        None => return (None, None),
+    };
+    let Some((merged, rest)) = rest.split_once('\'') else {
+        return (None, None);
+    };
+    let into = rest
+        .split_once(" into ")
+        .map(|(_, into)| into.trim().to_string())
+        .filter(|into| !into.is_empty());
+
+    (Some(merged.to_string()), into)
+}
+
+#[tauri::command]
+pub fn author_commit_counts(
+    repo_path: String,
+    since: Option<i64>,
+    until: Option<i64>,
+) -> Result<Vec<AuthorStat>, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let mailmap = repo.mailmap().map_err(|e| e.to_string())?;
+
+    let mut walk = repo.revwalk().map_err(|e| e.to_string())?;
+    push_history_roots(&repo, &mut walk)?;
+
+    // Tally by email so the same person with different spellings of their name is counted once
+    let mut stats: HashMap<String, AuthorStat> = HashMap::new();
+
+    for oid in walk {
+        let oid = oid.map_err(|e| e.to_string())?;
+        let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+
+        if !in_date_range(commit.time().seconds(), since, until) {
+            continue;
+        }
+
+        let author = commit
+            .author_with_mailmap(&mailmap)
+            .map_err(|e| e.to_string())?;
+        let name = author.name().unwrap_or("").to_string();
+        let email = author.email().unwrap_or("").to_string();
+
+        stats
+            .entry(email.clone())
+            .or_insert(AuthorStat {
+                name,
+                email,
+                count: 0,
+            })
+            .count += 1;
+    }
+
+    let mut stats: Vec<AuthorStat> = stats.into_values().collect();
+    stats.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+
+    Ok(stats)
+}
+
+// Cap for get_commit_trend, a daily trend over decades would be a huge payload
+const MAX_TREND_BUCKETS: usize = 10_000;
+
+// Commit counts per day, week or month between `since` and `until`, oldest first
+#[tauri::command]
+pub fn get_commit_trend(
+    repo_path: String,
+    bucket: String,
+    since: Option<i64>,
+    until: Option<i64>,
+) -> Result<Vec<BucketCount>, String> {
+    if !matches!(bucket.as_str(), "day" | "week" | "month") {
+        return Err(format!(
+            "Invalid bucket '{}'. Expected \"day\", \"week\" or \"month\"",
+            bucket
+        ));
+    }
+
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let mut walk = repo.revwalk().map_err(|e| e.to_string())?;
+    push_history_roots(&repo, &mut walk)?;
+
+    let mut counts: HashMap<chrono::NaiveDate, usize> = HashMap::new();
+    for oid in walk {
+        let oid = oid.map_err(|e| e.to_string())?;
+        let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+
+        let when = commit.author().when();
+        if !in_date_range(when.seconds(), since, until) {
+            continue;
+        }
+
+        // Bucket by the author's own calendar day, not UTC
+        let date = chrono::FixedOffset::east_opt(when.offset_minutes() * 60)
+            .and_then(|offset| {
+                chrono::DateTime::from_timestamp(when.seconds(), 0)
+                    .map(|time| time.with_timezone(&offset).date_naive())
+            })
+            .ok_or_else(|| format!("Invalid author time on commit {}", oid))?;
+        *counts.entry(bucket_start(date, &bucket)).or_insert(0) += 1;
+    }
+
+    // The requested range decides where the zero-filled gaps start and end
+    let to_date = |time: i64| chrono::DateTime::from_timestamp(time, 0).map(|t| t.date_naive());
+    let first = since
+        .and_then(to_date)
+        .map(|date| bucket_start(date, &bucket))
+        .or_else(|| counts.keys().min().copied());
+    // Nothing can be counted after today or the newest commit, so a far-future `until` stops there
+    let today = bucket_start(chrono::Utc::now().date_naive(), &bucket);
+    let newest = counts
+        .keys()
+        .max()
+        .map_or(today, |newest| (*newest).max(today));
+    let last = until
+        .and_then(to_date)
+        .map(|date| bucket_start(date, &bucket).min(newest))
+        .or_else(|| counts.keys().max().copied());
+
+    let mut trend = Vec::new();
+    if let (Some(first), Some(last)) = (first, last) {
+        let mut current = Some(first);
+        while let Some(date) = current.filter(|date| *date <= last) {
+            if trend.len() == MAX_TREND_BUCKETS {
+                return Err(format!(
+                    "The range has more than {} {} buckets, use a shorter range or a larger bucket",
+                    MAX_TREND_BUCKETS, bucket
+                ));
+            }
+            trend.push(BucketCount {
+                start: date.format("%Y-%m-%d").to_string(),
+                count: counts.get(&date).copied().unwrap_or(0),
+            });
+            // None once chrono's last representable date is reached
+            current = match bucket.as_str() {
+                "day" => date.checked_add_days(chrono::Days::new(1)),
+                "week" => date.checked_add_days(chrono::Days::new(7)),
+                _ => date.checked_add_months(chrono::Months::new(1)),
+            };
+        }
+    }
+
+    Ok(trend)
+}
+
+// Helper function to find the first day of the day/week/month bucket containing `date`
+fn bucket_start(date: chrono::NaiveDate, bucket: &str) -> chrono::NaiveDate {
+    use chrono::Datelike;
+
+    match bucket {
+        "week" => {
+            let offset = chrono::Days::new(date.weekday().num_days_from_monday() as u64);
+            date.checked_sub_days(offset).unwrap_or(date)
+        }
+        "month" => date.with_day(1).unwrap_or(date),
+        _ => date,
+    }
+}
+
+#[tauri::command]
+pub fn get_default_signature(repo_path: String) -> Result<SignatureInfo, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let signature = default_signature(&repo)?;
+
+    Ok(SignatureInfo {
+        name: signature.name().unwrap_or("").to_string(),
+        email: signature.email().unwrap_or("").to_string(),
+    })
+}
+
+// Helper function to build the signature git would use from user.name/user.email
+fn default_signature(repo: &Repository) -> Result<git2::Signature<'static>, String> {
+    repo.signature().map_err(|e| {
+        if e.code() == git2::ErrorCode::NotFound {
+            // Prefixed so the frontend can recognize it and prompt for an identity
+            "IdentityUnconfigured: Please set user.name and user.email in your git config."
+                .to_string()
+        } else {
+            format!("Failed to resolve signature: {}", e)
+        }
+    })
+}
+
+// Helper function to build a signature for an explicitly chosen identity (e.g. a pair
+// programming partner), stamped with the current time
+fn explicit_signature(name: &str, email: &str) -> Result<git2::Signature<'static>, String> {
+    let name = name.trim();
+    let email = email.trim();
+    if name.is_empty() {
+        return Err("Signature name cannot be empty".to_string());
+    }
+
+    // Deliberately loose: something@something, without characters that break the header
+    let valid_email = match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.is_empty()
+                && !email.contains(|c: char| c.is_whitespace() || c == '<' || c == '>')
+        }
+        None => false,
+    };
+    if !valid_email {
+        return Err(format!("Invalid email address '{}'", email));
+    }
+
+    git2::Signature::now(name, email).map_err(|e| format!("Invalid signature: {}", e))
+}
+
+// Layout constants for the exported SVG graph
+const SVG_LANE_WIDTH: usize = 20;
+const SVG_ROW_HEIGHT: usize = 28;
+const SVG_NODE_RADIUS: usize = 6;
+const SVG_PADDING: usize = 16;
+
+#[tauri::command]
+pub fn export_graph_svg(repo_path: String, limit: usize) -> Result<String, String> {
+    let response = get_commits(
+        repo_path, limit, None, None, None, None, None, None, None, None, None, None, None, None,
+    )?;
+    let commits = response.commits;
+
+    let lanes = assign_lanes(&commits);
+    let lane_count = lanes.iter().map(|lane| lane + 1).max().unwrap_or(1);
+
+    let row_of: HashMap<&str, usize> = commits
+        .iter()
+        .enumerate()
+        .map(|(row, commit)| (commit.id.as_str(), row))
+        .collect();
+
+    let position = |row: usize| -> (usize, usize) {
+        (
+            SVG_PADDING + lanes[row] * SVG_LANE_WIDTH,
+            SVG_PADDING + row * SVG_ROW_HEIGHT,
+        )
+    };
+
+    let text_x = SVG_PADDING * 2 + lane_count * SVG_LANE_WIDTH;
+    let width = text_x + 600;
+    let height = SVG_PADDING * 2 + commits.len().max(1) * SVG_ROW_HEIGHT;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" font-family=\"sans-serif\" font-size=\"12\">\n",
+        width, height, width, height
+    );
+    svg.push_str(&format!(
+        "<rect width=\"{}\" height=\"{}\" fill=\"#ffffff\"/>\n",
+        width, height
+    ));
+
+    // Edges first so nodes are drawn on top
+    for (row, commit) in commits.iter().enumerate() {
+        let (x1, y1) = position(row);
+        let color = edge_color(commit);
+
+        for (index, parent) in commit.parents.iter().enumerate() {
+            // Skip parents that didn't make it into this page
+            let Some(&parent_row) = row_of.get(parent.as_str()) else {
+                continue;
+            };
+            let (x2, y2) = position(parent_row);
+
+            // Same styling rule as the live graph: merged-in parents are dashed
+            let dash = if index > 0 {
+                " stroke-dasharray=\"4,4\""
+            } else {
+                ""
+            };
+            let path = if x1 == x2 {
+                format!("M {} {} L {} {}", x1, y1, x2, y2)
+            } else {
+                // Bend into the parent's lane within one row, then go straight down
+                let bend_y = y1 + SVG_ROW_HEIGHT;
+                format!(
+                    "M {} {} C {} {} {} {} {} {} L {} {}",
+                    x1, y1, x1, bend_y, x2, y1, x2, bend_y, x2, y2
+                )
+            };
+            svg.push_str(&format!(
+                "<path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"{}/>\n",
+                path, color, dash
+            ));
+        }
+    }
+
+    for (row, commit) in commits.iter().enumerate() {
+        let (x, y) = position(row);
+        svg.push_str(&format!(
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\" stroke=\"#ffffff\" stroke-width=\"2\"/>\n",
+            x,
+            y,
+            SVG_NODE_RADIUS,
+            edge_color(commit)
+        ));
+
+        let mut label = String::new();
+        if !commit.refs.is_empty() {
+            label.push_str(&format!("({}) ", commit.refs.join(", ")));
+        }
+        label.push_str(&commit.message);
+
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" dominant-baseline=\"middle\" fill=\"#111827\">{}</text>\n",
+            text_x,
+            y,
+            escape_xml(&label)
+        ));
+    }
+
+    svg.push_str("</svg>\n");
+
+    Ok(svg)
+}
+
+// Helper function to assign each commit (newest first) a lane index.
+// Each lane tracks the commit it is waiting for, so a commit continues the lane of its
+// first child and additional parents open new lanes. Deterministic for a given input order.
+fn assign_lanes(commits: &[GitCommit]) -> Vec<usize> {
+    let present: std::collections::HashSet<&str> =
+        commits.iter().map(|commit| commit.id.as_str()).collect();
+    let mut waiting: Vec<Option<&str>> = Vec::new();
+    let mut result = Vec::with_capacity(commits.len());
+
+    for commit in commits {
+        let id = commit.id.as_str();
+
+        let lane = match waiting.iter().position(|w| *w == Some(id)) {
+            Some(lane) => lane,
+            None => match waiting.iter().position(|w| w.is_none()) {
+                Some(free) => free,
+                None => {
+                    waiting.push(None);
+                    waiting.len() - 1
+                }
+            },
+        };
+
+        // Other lanes converging on this commit end here
+        for w in waiting.iter_mut() {
+            if *w == Some(id) {
+                *w = None;
+            }
+        }
+
+        let mut parents = commit
+            .parents
+            .iter()
+            .map(|p| p.as_str())
+            .filter(|p| present.contains(p));
+
+        waiting[lane] = parents.next();
+
+        for parent in parents {
+            if waiting.contains(&Some(parent)) {
+                continue;
+            }
+            match waiting.iter().position(|w| w.is_none()) {
+                Some(free) => waiting[free] = Some(parent),
+                None => waiting.push(Some(parent)),
+            }
+        }
+
+        result.push(lane);
+    }
+
+    result
+}
+
+// Helper function to color a commit like the frontend does (hue hashed from the author)
+fn edge_color(commit: &GitCommit) -> String {
+    if commit.is_virtual {
+        return "#6b7280".to_string();
+    }
+
+    // Same hash as getAuthorHue in graphUtils.ts, including JS int32 shift semantics
+    let mut hash: i64 = 0;
+    for unit in commit.author.encode_utf16() {
+        hash = unit as i64 + ((((hash as i32) << 5) as i64) - hash);
+    }
+    format!("hsl({}, 60%, 60%)", (hash % 360).abs())
+}
+
+// Helper function to escape text for use inside SVG/XML
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Caps for find_large_blobs so huge histories don't produce huge payloads
+const MAX_LARGE_BLOBS: usize = 200;
+const MAX_LARGE_BLOB_LOCATIONS: usize = 20;
+
+// Results of find_large_blobs keyed by (repo path, min size), valid while the ref tips are unchanged
+type LargeBlobCache = HashMap<(String, usize), (Vec<Oid>, Vec<LargeBlob>)>;
+static LARGE_BLOB_CACHE: std::sync::OnceLock<std::sync::Mutex<LargeBlobCache>> =
+    std::sync::OnceLock::new();
+
+#[tauri::command]
+pub fn find_large_blobs(repo_path: String, min_size: usize) -> Result<Vec<LargeBlob>, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    let tips = ref_tips(&repo);
+    let cache_key = (repo_path.clone(), min_size);
+    let cache = LARGE_BLOB_CACHE.get_or_init(Default::default);
+    if let Some((cached_tips, blobs)) = cache.lock().unwrap().get(&cache_key) {
+        if *cached_tips == tips {
+            return Ok(blobs.clone());
+        }
+    }
+
+    let odb = repo.odb().map_err(|e| e.to_string())?;
+
+    // Walk every ref, including remote tracking branches, so nothing reachable is missed
+    // Oldest first, so the first commit a blob is seen in is where it was introduced
+    let mut walk = repo.revwalk().map_err(|e| e.to_string())?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME | Sort::REVERSE)
+        .map_err(|e| e.to_string())?;
+    let _ = walk.push_head();
+    walk.push_glob("*").map_err(|e| e.to_string())?;
+
+    let mut visited_trees: std::collections::HashSet<Oid> = std::collections::HashSet::new();
+    let mut sizes: HashMap<Oid, usize> = HashMap::new();
+    let mut found: HashMap<Oid, LargeBlob> = HashMap::new();
+
+    for oid in walk {
+        let oid = oid.map_err(|e| e.to_string())?;
+        let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+        let tree = commit.tree().map_err(|e| e.to_string())?;
+
+        if !visited_trees.insert(tree.id()) {
+            continue;
+        }
+
+        tree.walk(git2::TreeWalkMode::PreOrder, |root, entry| {
+            match entry.kind() {
+                // Identical subtrees were already scanned through an earlier commit
+                Some(ObjectType::Tree) => {
+                    if visited_trees.insert(entry.id()) {
+                        git2::TreeWalkResult::Ok
+                    } else {
+                        git2::TreeWalkResult::Skip
+                    }
+                }
+                Some(ObjectType::Blob) => {
+                    // Reading the header gives the size without loading the content
+                    let size = *sizes.entry(entry.id()).or_insert_with(|| {
+                        odb.read_header(entry.id())
+                            .map(|(size, _)| size)
+                            .unwrap_or(0)
+                    });
+
+                    if size >= min_size {
+                        let blob = found.entry(entry.id()).or_insert_with(|| LargeBlob {
+                            oid: entry.id().to_string(),
+                            size,
+                            paths: vec![],
+                            commits: vec![],
+                        });
+
+                        let path = format!("{}{}", root, entry.name().unwrap_or(""));
+                        if blob.paths.len() < MAX_LARGE_BLOB_LOCATIONS
+                            && !blob.paths.contains(&path)
+                        {
+                            blob.paths.push(path);
+                        }
+                        let commit_id = oid.to_string();
+                        if blob.commits.len() < MAX_LARGE_BLOB_LOCATIONS
+                            && !blob.commits.contains(&commit_id)
+                        {
+                            blob.commits.push(commit_id);
+                        }
+                    }
+                    git2::TreeWalkResult::Ok
+                }
+                _ => git2::TreeWalkResult::Ok,
+            }
+        })
+        .map_err(|e| e.to_string())?;
+    }
+
+    let mut blobs: Vec<LargeBlob> = found.into_values().collect();
+    blobs.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.oid.cmp(&b.oid)));
+    blobs.truncate(MAX_LARGE_BLOBS);
+
+    cache
+        .lock()
+        .unwrap()
+        .insert(cache_key, (tips, blobs.clone()));
+
+    Ok(blobs)
+}
+
+// Results of get_commit_time_range keyed by repo path, valid while the ref tips are unchanged
+type TimeRangeCache = HashMap<String, (Vec<Oid>, (i64, i64))>;
+static TIME_RANGE_CACHE: std::sync::OnceLock<std::sync::Mutex<TimeRangeCache>> =
+    std::sync::OnceLock::new();
+
+#[tauri::command]
+pub fn get_commit_time_range(repo_path: String) -> Result<(i64, i64), String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    // New commits always move a ref tip, so the tips are enough to detect a stale range
+    let tips = ref_tips(&repo);
+    let cache = TIME_RANGE_CACHE.get_or_init(Default::default);
+    if let Some((cached_tips, range)) = cache.lock().unwrap().get(&repo_path) {
+        if *cached_tips == tips {
+            return Ok(*range);
+        }
+    }
+
+    // Same roots as get_commits, so the range matches what the graph can show.
+    // Commit dates aren't monotonic, so every commit has to be looked at, but an
+    // unsorted walk avoids the cost of sorting
+    let mut walk = repo.revwalk().map_err(|e| e.to_string())?;
+    push_history_roots(&repo, &mut walk)?;
+
+    let mut range: Option<(i64, i64)> = None;
+    for oid in walk {
+        let oid = oid.map_err(|e| e.to_string())?;
+        let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+        let time = commit.time().seconds();
+        range = Some(match range {
+            Some((min, max)) => (min.min(time), max.max(time)),
+            None => (time, time),
+        });
+    }
+
+    let range =
+        range.ok_or_else(|| "EmptyRepository: The repository has no commits yet.".to_string())?;
+
+    cache.lock().unwrap().insert(repo_path, (tips, range));
+
+    Ok(range)
+}
+
+// Cap for find_dangling_commits, a repository with lots of abandoned work can have thousands
+const MAX_DANGLING_COMMITS: usize = 200;
+
+// Like `git fsck --lost-found` restricted to commits. This enumerates the whole object
+// database, so it can be slow on large repositories
+#[tauri::command]
+pub fn find_dangling_commits(repo_path: String) -> Result<Vec<GitCommit>, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let odb = repo.odb().map_err(|e| e.to_string())?;
+
+    // Everything reachable from a ref or a reflog entry is not lost
+    let mut walk = repo.revwalk().map_err(|e| e.to_string())?;
+    let _ = walk.push_head();
+    let mut log_names = vec!["HEAD".to_string()];
+    if let Ok(refs) = repo.references() {
+        for r in refs.flatten() {
+            if let Some(target) = r.target() {
+                // Refs can point at non-commits (e.g. tagged blobs), which revwalk rejects
+                let _ = walk.push(target);
+            }
+            if let Some(name) = r.name() {
+                log_names.push(name.to_string());
+            }
+        }
+    }
+    for name in &log_names {
+        if let Ok(reflog) = repo.reflog(name) {
+            for entry in reflog.iter() {
+                for oid in [entry.id_old(), entry.id_new()] {
+                    if !oid.is_zero() {
+                        // Objects of old entries may have been pruned already
+                        let _ = walk.push(oid);
+                    }
+                }
+            }
+        }
+    }
+    let mut reachable = std::collections::HashSet::new();
+    for oid in walk {
+        reachable.insert(oid.map_err(|e| e.to_string())?);
+    }
+
+    let mut unreachable = Vec::new();
+    odb.foreach(|oid| {
+        if !reachable.contains(oid) && matches!(odb.read_header(*oid), Ok((_, ObjectType::Commit)))
+        {
+            unreachable.push(*oid);
+        }
+        true
+    })
+    .map_err(|e| e.to_string())?;
+
+    // Only report the tips: recovering a tip also recovers its lost ancestors
+    let mut commits = Vec::new();
+    let mut lost_parents = std::collections::HashSet::new();
+    for oid in &unreachable {
+        let commit = repo.find_commit(*oid).map_err(|e| e.to_string())?;
+        lost_parents.extend(commit.parent_ids());
+        commits.push(commit);
+    }
+    commits.retain(|commit| !lost_parents.contains(&commit.id()));
+
+    // Most recently lost work first
+    commits.sort_by_key(|commit| std::cmp::Reverse(commit.time().seconds()));
+    commits.truncate(MAX_DANGLING_COMMITS);
+
+    Ok(commits.iter().map(undecorated_commit).collect())
+}
+
+// Helper function to build a GitCommit without refs, for commits listed outside the main graph
+fn undecorated_commit(commit: &git2::Commit) -> GitCommit {
+    let author = commit.author();
+    let committer = commit.committer();
+    GitCommit {
+        id: commit.id().to_string(),
+        message: commit.summary().unwrap_or("").to_string(),
+        body: commit.body().map(|body| body.to_string()),
+        author: author.name().unwrap_or("").to_string(),
+        author_email: author.email().unwrap_or("").to_string(),
+        author_date: author.when().seconds(),
+        committer: committer.name().unwrap_or("").to_string(),
+        committer_email: committer.email().unwrap_or("").to_string(),
+        committer_date: committer.when().seconds(),
+        date: commit.time().seconds(),
+        parents: commit.parent_ids().map(|p| p.to_string()).collect(),
+        refs: vec![],
+        head_type: None,
+        uncommitted_state: None,
+        is_virtual: false,
+        parent_summaries: None,
+        stats: None,
+    }
+}
+
+// Commits on `candidate_branch` that make the same change as `commit_id` (like `git cherry`),
+// e.g. its rebased copy. Compared by patch-id, so the OIDs and messages may differ.
+#[tauri::command]
+pub fn find_equivalent_commits(
+    repo_path: String,
+    commit_id: String,
+    candidate_branch: String,
+) -> Result<Vec<String>, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    let oid = Oid::from_str(&commit_id)
+        .map_err(|e| format!("Invalid commit ID '{}': {}", commit_id, e))?;
+    let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+    let candidate_oid = repo
+        .revparse_single(&candidate_branch)
+        .and_then(|object| object.peel_to_commit())
+        .map(|commit| commit.id())
+        .map_err(|e| {
+            format!(
+                "NotFound: '{}' is not a branch or commit: {}",
+                candidate_branch, e
+            )
+        })?;
+
+    let Some(patch_id) = commit_patch_id(&repo, &commit)? else {
+        return Ok(vec![]);
+    };
+
+    // Commits already in commit_id's history are the original, not a copy of it
+    let mut walk = repo.revwalk().map_err(|e| e.to_string())?;
+    walk.push(candidate_oid).map_err(|e| e.to_string())?;
+    walk.hide(oid).map_err(|e| e.to_string())?;
+
+    let mut matches = Vec::new();
+    for candidate in walk {
+        let candidate = candidate.map_err(|e| e.to_string())?;
+        let candidate_commit = repo.find_commit(candidate).map_err(|e| e.to_string())?;
+        if commit_patch_id(&repo, &candidate_commit)? == Some(patch_id) {
+            matches.push(candidate.to_string());
+        }
+    }
+
+    Ok(matches)
+}
+
+// Helper function to compute the patch-id (`git patch-id --stable`) of a commit's change.
+// None for merges and empty commits, which git cherry doesn't match either.
+fn commit_patch_id(repo: &Repository, commit: &git2::Commit) -> Result<Option<Oid>, String> {
+    if commit.parent_count() > 1 {
+        return Ok(None);
+    }
+    let tree = commit.tree().map_err(|e| e.to_string())?;
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree().map_err(|e| e.to_string())?),
+        Err(_) => None,
+    };
+
+    let diff = repo
+        .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+        .map_err(|e| e.to_string())?;
+    if diff.deltas().len() == 0 {
+        return Ok(None);
+    }
+    diff.patchid(None).map(Some).map_err(|e| e.to_string())
+}
+
+// How far back find_fixup_commits looks, so a clean history doesn't walk the whole repository
+const FIXUP_SCAN_DEPTH: usize = 1000;
+
+// Commits on HEAD marked for cleanup before pushing (`fixup!`, `squash!` or WIP), newest first
+#[tauri::command]
+pub fn find_fixup_commits(repo_path: String, limit: usize) -> Result<Vec<GitCommit>, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    let mut walk = repo.revwalk().map_err(|e| e.to_string())?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
+        .map_err(|e| e.to_string())?;
+    if walk.push_head().is_err() {
+        // Unborn HEAD: no history to clean up
+        return Ok(vec![]);
+    }
+
+    let mut commits = Vec::new();
+    for oid in walk.take(FIXUP_SCAN_DEPTH) {
+        if commits.len() >= limit {
+            break;
+        }
+        let oid = oid.map_err(|e| e.to_string())?;
+        let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+        if is_fixup_summary(commit.summary().unwrap_or("")) {
+            commits.push(undecorated_commit(&commit));
+        }
+    }
+
+    Ok(commits)
+}
+
+// Helper function to match the summaries `git rebase --autosquash` acts on, plus WIP markers.
+// WIP has to be a whole word, so "WIPE cache" or "Wiping" don't count.
+fn is_fixup_summary(summary: &str) -> bool {
+    if summary.starts_with("fixup! ") || summary.starts_with("squash! ") {
+        return true;
+    }
+    match summary.get(..3) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("wip") => summary[3..]
+            .chars()
+            .next()
+            .is_none_or(|c| !c.is_alphanumeric()),
+        _ => false,
+    }
+}
+
+// Helper function to collect the sorted targets of all refs, used to invalidate caches
+fn ref_tips(repo: &Repository) -> Vec<Oid> {
+    let mut tips: Vec<Oid> = repo
+        .references()
+        .map(|refs| refs.flatten().filter_map(|r| r.target()).collect())
+        .unwrap_or_default();
+    if let Ok(head) = repo.head() {
+        tips.extend(head.target());
+    }
+    tips.sort();
+    tips
+}
+
+// Helper function to seed a revwalk with HEAD, all local branches and tags
+fn push_history_roots(repo: &Repository, walk: &mut git2::Revwalk) -> Result<(), String> {
+    // Instead of only pushing HEAD, push all references to ensure we get complete history
+    // This ensures that even in detached HEAD state, we can see the full commit graph
+
+    // Push HEAD if it exists
+    if let Ok(head) = repo.head() {
+        if let Some(target) = head.target() {
+            walk.push(target).map_err(|e| e.to_string())?;
+        }
+    }
+
+    // Push all local branches and tags
+    if let Ok(refs) = repo.references() {
+        for r in refs.flatten() {
+            if let Some(name) = r.name() {
+                // Only include local branches and tags, not remote tracking branches
+                if name.starts_with("refs/heads/") || name.starts_with("refs/tags/") {
+                    if let Some(target) = r.target() {
+                        // Ignore errors when pushing (some refs might be invalid)
+                        let _ = walk.push(target);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Working-copy stats are skipped above this many changed files, since every file has to be diffed
+const MAX_WORKING_COPY_STATS_FILES: usize = 1000;
+
+// Helper function to count the uncommitted changes (staged, unstaged and untracked) against HEAD
+fn working_copy_stats(
+    repo: &Repository,
+    path_filter: Option<&str>,
+    exclude_paths: &PathExcludes,
+) -> Option<CommitStats> {
+    let head_tree = repo.head().ok()?.peel_to_tree().ok()?;
+
+    let mut diff_opts = DiffOptions::new();
+    diff_opts
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+    if let Some(path) = path_filter {
+        diff_opts.pathspec(path);
+    }
+
+    let diff = repo
+        .diff_tree_to_workdir_with_index(Some(&head_tree), Some(&mut diff_opts))
+        .ok()?;
+    if diff.deltas().len() > MAX_WORKING_COPY_STATS_FILES {
+        return None;
+    }
+
+    let mut stats = CommitStats {
+        files_changed: 0,
+        additions: 0,
+        deletions: 0,
+    };
+    for (idx, delta) in diff.deltas().enumerate() {
+        let path = delta.new_file().path().or_else(|| delta.old_file().path());
+        if path.is_some_and(|path| exclude_paths.is_excluded(&path.to_string_lossy())) {
+            continue;
+        }
+
+        stats.files_changed += 1;
+        if let Some((_, additions, deletions)) = git2::Patch::from_diff(&diff, idx)
+            .ok()
+            .flatten()
+            .and_then(|patch| patch.line_stats().ok())
+        {
+            stats.additions += additions;
+            stats.deletions += deletions;
+        }
+    }
+
+    Some(stats)
+}
+
+// Helper function to count what a commit changed compared to its first parent (like `git show --stat`)
+fn commit_stats(repo: &Repository, commit: &git2::Commit) -> Option<CommitStats> {
+    let tree = commit.tree().ok()?;
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree().ok()?),
+        Err(_) => None, // Root commit: everything is new
+    };
+    let stats = repo
+        .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+        .and_then(|diff| diff.stats())
+        .ok()?;
+
+    Some(CommitStats {
+        files_changed: stats.files_changed(),
+        additions: stats.insertions(),
+        deletions: stats.deletions(),
+    })
+}
+
+// Helper function to check whether a commit time falls within an optional inclusive range
+fn in_date_range(time: i64, since: Option<i64>, until: Option<i64>) -> bool {
+    since.is_none_or(|since| time >= since) && until.is_none_or(|until| time <= until)
+}
+
+// Helper function to check whether a commit changed anything under `path` compared to its first parent
+fn commit_touches_path(
+    repo: &Repository,
+    commit: &git2::Commit,
+    path: &str,
+) -> Result<bool, String> {
+    let tree = commit.tree().map_err(|e| e.to_string())?;
+    // Root commits are compared against the empty tree
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree().map_err(|e| e.to_string())?),
+        Err(_) => None,
+    };
+
+    let mut diff_opts = DiffOptions::new();
+    diff_opts.pathspec(path);
+
+    let diff = repo
+        .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut diff_opts))
+        .map_err(|e| e.to_string())?;
+
+    // We only need to know whether any delta exists
+    Ok(diff.deltas().len() > 0)
+}
+
+// Helper function to find the path `path` was renamed from in `commit`, if it was renamed there
+fn renamed_from(
+    repo: &Repository,
+    commit: &git2::Commit,
+    path: &str,
+) -> Result<Option<String>, String> {
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => parent.tree().map_err(|e| e.to_string())?,
+        Err(_) => return Ok(None),
+    };
+    let tree = commit.tree().map_err(|e| e.to_string())?;
+
+    // A rename shows up as an addition of `path`, so only pay for rename detection in that case
+    if parent_tree.get_path(std::path::Path::new(path)).is_ok() {
+        return Ok(None);
+    }
+
+    // Rename detection needs to see the deleted side too, so the diff can't be limited to `path`
+    let mut diff = repo
+        .diff_tree_to_tree(Some(&parent_tree), Some(&tree), None)
+        .map_err(|e| e.to_string())?;
+    let mut find_opts = DiffFindOptions::new();
+    find_opts.renames(true);
+    diff.find_similar(Some(&mut find_opts))
+        .map_err(|e| e.to_string())?;
+
+    let old_path = diff
+        .deltas()
+        .find(|delta| {
+            delta.status() == Delta::Renamed
+                && delta.new_file().path().and_then(|p| p.to_str()) == Some(path)
+        })
+        .and_then(|delta| delta.old_file().path())
+        .map(|p| p.to_string_lossy().to_string());
+
+    Ok(old_path)
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn get_diff(
+    repo_path: String,
+    old_commit: String,
+    new_commit: String,
+    detect_moves: Option<bool>,
+    sort_by: Option<String>,
+    ignore_whitespace: Option<bool>,
+    ignore_whitespace_change: Option<bool>,
+    ignore_whitespace_eol: Option<bool>,
+    max_files: Option<usize>,
+    exclude_paths: Option<Vec<String>>,
+    image_previews: Option<bool>,
+    include_untracked: Option<bool>,
+) -> Result<DiffResponse, String> {
+    // Renames and copies are always detected, this widens the search to unmodified sources
+    // and rewritten files
+    let detect_moves = detect_moves.unwrap_or(false);
+    let ignore_whitespace = ignore_whitespace.unwrap_or(false);
+    let ignore_whitespace_change = ignore_whitespace_change.unwrap_or(false);
+    let ignore_whitespace_eol = ignore_whitespace_eol.unwrap_or(false);
+    let ignores_any_whitespace =
+        ignore_whitespace || ignore_whitespace_change || ignore_whitespace_eol;
+
+    // Validate the sort key before doing any work
+    if let Some(key) = &sort_by {
+        if !matches!(key.as_str(), "path" | "size" | "status") {
+            return Err(format!(
+                "Invalid sort key '{}'. Expected \"path\", \"size\" or \"status\"",
+                key
+            ));
+        }
+    }
+    let exclude_paths = PathExcludes::parse(exclude_paths.unwrap_or_default())?;
+    log::debug!("get_diff called: old={}, new={}", old_commit, new_commit);
+
+    // Validate commit IDs, the working copy can only be the new side
+    if old_commit == WORKING_COPY_ID {
+        return Err(
+            "Cannot diff from working-copy. Please select it as the second commit.".to_string(),
+        );
+    }
+    let working_copy = new_commit == WORKING_COPY_ID;
+
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    let old_oid = Oid::from_str(&old_commit)
+        .map_err(|e| format!("Invalid commit ID '{}': {}", old_commit, e))?;
+    let old_commit_obj = repo.find_commit(old_oid).map_err(|e| e.to_string())?;
+    let old_tree = old_commit_obj.tree().map_err(|e| e.to_string())?;
+
+    // None when the new side is the working copy, whose content is read from disk
+    let new_tree = if working_copy {
+        None
+    } else {
+        let new_oid = Oid::from_str(&new_commit)
+            .map_err(|e| format!("Invalid commit ID '{}': {}", new_commit, e))?;
+        let new_commit_obj = repo.find_commit(new_oid).map_err(|e| e.to_string())?;
+        Some(new_commit_obj.tree().map_err(|e| e.to_string())?)
+    };
+
+    let mut diff_opts = DiffOptions::new();
+    if detect_moves {
+        // Unmodified files are needed as potential copy sources
+        diff_opts.include_unmodified(true);
+    }
+    diff_opts
+        .ignore_whitespace(ignore_whitespace) // git diff -w
+        .ignore_whitespace_change(ignore_whitespace_change) // git diff -b
+        .ignore_whitespace_eol(ignore_whitespace_eol); // git diff --ignore-space-at-eol
+
+    let mut diff = match &new_tree {
+        Some(new_tree) => {
+            repo.diff_tree_to_tree(Some(&old_tree), Some(new_tree), Some(&mut diff_opts))
+        }
+        None => {
+            // By default the same files as the Uncommitted Changes node, untracked ones show up
+            // as additions
+            let include_untracked = include_untracked.unwrap_or(true);
+            diff_opts
+                .include_untracked(include_untracked)
+                .recurse_untracked_dirs(include_untracked);
+            repo.diff_tree_to_workdir_with_index(Some(&old_tree), Some(&mut diff_opts))
+        }
+    }
+    .map_err(|e| e.to_string())?;
+
+    // Renames and copies between changed files are cheap to find and keep
+    // refactors from showing up as delete + add pairs
+    let mut find_opts = DiffFindOptions::new();
+    find_opts.renames(true).copies(true);
+    if detect_moves {
+        // Opt-in because it's expensive: compares every changed file against
+        // every other file to find content that was moved or copied between files
+        find_opts
+            .copies_from_unmodified(true)
+            .rewrites(true)
+            .break_rewrites(true)
+            .for_untracked(true);
+    }
+    diff.find_similar(Some(&mut find_opts))
+        .map_err(|e| e.to_string())?;
+
+    log::debug!("Diff object created, getting deltas...");
+
+    // Each file is kept together with its change size and status rank for sorting
+    let mut files = Vec::new();
+
+    for (idx, delta) in diff.deltas().enumerate() {
+        log::debug!("Processing delta");
+
+        // Only present because of include_unmodified, nothing to show
+        if delta.status() == Delta::Unmodified {
+            continue;
+        }
+
+        // Deltas are based on blob OIDs, so whitespace-only changes only disappear from the patch
+        if ignores_any_whitespace && delta.status() == Delta::Modified {
+            let has_hunks = git2::Patch::from_diff(&diff, idx)
+                .ok()
+                .flatten()
+                .is_some_and(|patch| patch.num_hunks() > 0);
+            if !has_hunks {
+                continue;
+            }
+        }
+
+        // Get the file path from either new_file or old_file
+        let file_path = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path())
+            .map(|p| {
+                let path_str = p.to_string_lossy().to_string();
+                log::debug!("Processing file: {}", path_str);
+                path_str
+            })
+            .unwrap_or_else(|| {
+                log::debug!("Unknown file path in delta");
+                String::from("unknown")
+            });
+
+        // Untracked files only appear in working copy diffs, where they are new files
+        let status = match delta.status() {
+            Delta::Untracked => Delta::Added,
+            status => status,
+        };
+        let old_path = delta_old_path(&delta, &file_path);
+
+        if exclude_paths.is_excluded(&file_path) {
+            continue;
+        }
+
+        // Only compute line stats when sorting by size, since it requires generating the patch
+        let change_size = if sort_by.as_deref() == Some("size") {
+            git2::Patch::from_diff(&diff, idx)
+                .ok()
+                .flatten()
+                .and_then(|patch| patch.line_stats().ok())
+                .map(|(_, additions, deletions)| additions + deletions)
+                .unwrap_or(0)
+        } else {
+            0
+        };
+
+        // Content is loaded after truncation so files beyond max_files cost nothing
+        files.push((
+            FileDiff {
+                path: file_path,
+                old_content: String::new(),
+                new_content: String::new(),
+                status: delta_status_name(status).to_string(),
+                old_path,
+                old_content_base64: None,
+                new_content_base64: None,
+                binary: false,
+                old_size: None,
+                new_size: None,
+            },
+            change_size,
+            status_rank(status),
+            idx,
+        ));
+    }
+
+    // Tree order is kept when no sort key is given
+    match sort_by.as_deref() {
+        Some("path") => files.sort_by(|a, b| a.0.path.cmp(&b.0.path)),
+        // Biggest changes first
+        Some("size") => files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.path.cmp(&b.0.path))),
+        Some("status") => files.sort_by(|a, b| a.2.cmp(&b.2).then_with(|| a.0.path.cmp(&b.0.path))),
+        _ => {}
+    }
+
+    let total_files = files.len();
+    let files_truncated = max_files.is_some_and(|max| total_files > max);
+    if let Some(max) = max_files {
+        files.truncate(max);
+    }
+
+    let mut result = Vec::with_capacity(files.len());
+    for (mut file, _, _, idx) in files {
+        let delta = match diff.get_delta(idx) {
+            Some(delta) => delta,
+            None => continue,
+        };
+
+        let workdir_bytes = classify_delta_content(&repo, &delta, working_copy, &mut file);
+
+        // Get old content
+        file.old_content = if file.binary {
+            String::new()
+        } else if let Some(old_path) = delta.old_file().path() {
+            log::debug!("Getting old content from: {:?}", old_path);
+            get_file_content(&repo, &old_tree, &file.path, Some(old_path))
+        } else {
+            log::debug!("File was added (no old content)");
+            String::new() // File was added
+        };
+
+        // Get new content
+        file.new_content = if file.binary {
+            String::new()
+        } else if let Some(bytes) = &workdir_bytes {
+            String::from_utf8_lossy(bytes).to_string()
+        } else if let (Some(new_tree), Some(new_path)) = (&new_tree, delta.new_file().path()) {
+            log::debug!("Getting new content from: {:?}", new_path);
+            get_file_content(&repo, new_tree, &file.path, Some(new_path))
+        } else {
+            log::debug!("File was deleted (no new content)");
+            String::new() // File was deleted
+        };
+
+        // Images are sent as bytes so the UI can show them side by side
+        if image_previews.unwrap_or(false) && is_image_path(&file.path) {
+            file.old_content_base64 = image_base64(&repo, &delta.old_file());
+            file.new_content_base64 = match &workdir_bytes {
+                Some(bytes) => workdir_image_base64(bytes),
+                None => image_base64(&repo, &delta.new_file()),
+            };
+            if file.old_content_base64.is_some() || file.new_content_base64.is_some() {
+                // The lossy text version of an image is useless and only inflates the payload
+                file.old_content.clear();
+                file.new_content.clear();
+            }
+        }
+
+        result.push(file);
+    }
+
+    log::debug!("Returning {} files", result.len());
+    Ok(DiffResponse {
+        files: result,
+        files_truncated,
+        total_files,
+    })
+}
+
+// What changed in the working tree since `commit_id`, which doesn't have to be HEAD
+#[tauri::command]
+pub fn diff_workdir_to_commit(
+    repo_path: String,
+    commit_id: String,
+    include_untracked: Option<bool>,
+) -> Result<DiffResponse, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let found = Oid::from_str(&commit_id)
+        .ok()
+        .is_some_and(|oid| repo.find_commit(oid).is_ok());
+    if !found {
+        return Err(format!("NotFound: Commit '{}' not found", commit_id));
+    }
+
+    get_diff(
+        repo_path,
+        commit_id,
+        WORKING_COPY_ID.to_string(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(include_untracked.unwrap_or(false)),
+    )
+}
+
+// Change counts between two commits grouped by top-level directory, without any file content
+#[tauri::command]
+pub fn get_diff_tree_summary(
+    repo_path: String,
+    old: String,
+    new: String,
+) -> Result<Vec<DirSummary>, String> {
+    if old == WORKING_COPY_ID || new == WORKING_COPY_ID {
+        return Err("Cannot diff with working-copy. Please select real commits.".to_string());
+    }
+
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    let old_tree = commit_tree(&repo, &old)?;
+    let new_tree = commit_tree(&repo, &new)?;
+
+    let diff = repo
+        .diff_tree_to_tree(Some(&old_tree), Some(&new_tree), None)
+        .map_err(|e| e.to_string())?;
+
+    let mut summaries: std::collections::BTreeMap<String, DirSummary> =
+        std::collections::BTreeMap::new();
+    for (idx, delta) in diff.deltas().enumerate() {
+        let path = match delta.new_file().path().or_else(|| delta.old_file().path()) {
+            Some(path) => path.to_string_lossy().to_string(),
+            None => continue,
+        };
+        let directory = match path.split_once('/') {
+            Some((dir, _)) => dir.to_string(),
+            None => String::new(),
+        };
+
+        // Binary files have no line stats and only count towards files_changed
+        let (additions, deletions) = git2::Patch::from_diff(&diff, idx)
+            .ok()
+            .flatten()
+            .and_then(|patch| patch.line_stats().ok())
+            .map(|(_, additions, deletions)| (additions, deletions))
+            .unwrap_or((0, 0));
+
+        let summary = summaries
+            .entry(directory.clone())
+            .or_insert_with(|| DirSummary {
+                directory,
+                files_changed: 0,
+                additions: 0,
+                deletions: 0,
+            });
+        summary.files_changed += 1;
+        summary.additions += additions;
+        summary.deletions += deletions;
+    }
+
+    Ok(summaries.into_values().collect())
+}
+
+// Per-file hunks between two commits, so the frontend doesn't have to diff whole files itself
+#[tauri::command]
+pub fn get_diff_hunks(
+    repo_path: String,
+    old_commit: String,
+    new_commit: String,
+) -> Result<Vec<FileHunks>, String> {
+    if old_commit == WORKING_COPY_ID || new_commit == WORKING_COPY_ID {
+        return Err("Cannot diff with working-copy. Please select real commits.".to_string());
+    }
+
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let old_tree = commit_tree(&repo, &old_commit)?;
+    let new_tree = commit_tree(&repo, &new_commit)?;
+
+    let mut diff = repo
+        .diff_tree_to_tree(Some(&old_tree), Some(&new_tree), None)
+        .map_err(|e| e.to_string())?;
+    // Renames show up as one file instead of a deletion and an addition
+    diff.find_similar(None).map_err(|e| e.to_string())?;
+
+    let mut files = Vec::new();
+    for (idx, delta) in diff.deltas().enumerate() {
+        let path = match delta.new_file().path().or_else(|| delta.old_file().path()) {
+            Some(path) => path.to_string_lossy().to_string(),
+            None => continue,
+        };
+        let old_path = delta_old_path(&delta, &path);
+
+        let patch = git2::Patch::from_diff(&diff, idx).map_err(|e| e.to_string())?;
+        let binary = delta.flags().is_binary() || patch.is_none();
+
+        let mut hunks = Vec::new();
+        if let Some(patch) = patch.filter(|_| !binary) {
+            for hunk_idx in 0..patch.num_hunks() {
+                let (hunk, line_count) = patch.hunk(hunk_idx).map_err(|e| e.to_string())?;
+
+                let mut lines = Vec::with_capacity(line_count);
+                for line_idx in 0..line_count {
+                    let line = patch
+                        .line_in_hunk(hunk_idx, line_idx)
+                        .map_err(|e| e.to_string())?;
+                    let content = String::from_utf8_lossy(line.content());
+                    lines.push(DiffLine {
+                        origin: line.origin(),
+                        content: content.trim_end_matches(['\n', '\r']).to_string(),
+                        old_lineno: line.old_lineno(),
+                        new_lineno: line.new_lineno(),
+                    });
+                }
+
+                let header = String::from_utf8_lossy(hunk.header())
+                    .trim_end()
+                    .to_string();
+                hunks.push(DiffHunk {
+                    function_context: hunk_function_context(&header),
+                    header,
+                    old_start: hunk.old_start(),
+                    old_lines: hunk.old_lines(),
+                    new_start: hunk.new_start(),
+                    new_lines: hunk.new_lines(),
+                    lines,
+                });
+            }
+        }
+
+        files.push(FileHunks {
+            path,
+            old_path,
+            status: delta_status_name(delta.status()).to_string(),
+            binary,
+            hunks,
+        });
+    }
+
+    Ok(files)
+}
+
+// Helper function to extract the text libgit2 puts after the closing "@@" of a hunk header.
+// It comes from the xfuncname of the file's diff driver (.gitattributes) or the default heuristic.
+fn hunk_function_context(header: &str) -> Option<String> {
+    let rest = header.strip_prefix("@@")?;
+    let (_, context) = rest.split_once("@@")?;
+    let context = context.trim();
+    (!context.is_empty()).then(|| context.to_string())
+}
+
+// Helper function to look up the tree of a commit given by its full ID
+fn commit_tree<'r>(repo: &'r Repository, commit_id: &str) -> Result<git2::Tree<'r>, String> {
+    let oid = Oid::from_str(commit_id)
+        .map_err(|e| format!("Invalid commit ID '{}': {}", commit_id, e))?;
+    repo.find_commit(oid)
+        .and_then(|commit| commit.tree())
+        .map_err(|e| e.to_string())
+}
+
+// Helper function to name a delta status the way the frontend shows it
+fn delta_status_name(status: Delta) -> &'static str {
+    match status {
+        Delta::Added => "added",
+        Delta::Deleted => "deleted",
+        Delta::Modified => "modified",
+        Delta::Renamed => "renamed",
+        Delta::Copied => "copied",
+        Delta::Typechange => "typechange",
+        Delta::Untracked => "untracked",
+        Delta::Conflicted => "conflicted",
+        Delta::Ignored => "ignored",
+        Delta::Unreadable => "unreadable",
+        Delta::Unmodified => "unmodified",
+    }
+}
+
+// What `git commit` would record: the paths that differ between HEAD and the index.
+// Cheaper than a status scan since the working tree isn't read
+#[tauri::command]
+pub fn get_staged_paths(repo_path: String) -> Result<Vec<StagedPath>, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let index = repo.index().map_err(|e| e.to_string())?;
+
+    // Unborn HEAD: everything in the index is new
+    let head_tree = match repo.head() {
+        Ok(head) => Some(head.peel_to_tree().map_err(|e| e.to_string())?),
+        Err(_) => None,
+    };
+
+    let diff = repo
+        .diff_tree_to_index(head_tree.as_ref(), Some(&index), None)
+        .map_err(|e| e.to_string())?;
+
+    Ok(diff
+        .deltas()
+        .filter_map(|delta| {
+            let path = delta
+                .new_file()
+                .path()
+                .or_else(|| delta.old_file().path())?;
+            Some(StagedPath {
+                path: path.to_string_lossy().to_string(),
+                status: delta_status_name(delta.status()).to_string(),
+            })
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub fn get_staged_file_diff(repo_path: String, file_path: String) -> Result<FileDiff, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let index = repo.index().map_err(|e| e.to_string())?;
+
+    // Unborn HEAD: everything in the index is new
+    let head_tree = match repo.head() {
+        Ok(head) => Some(head.peel_to_tree().map_err(|e| e.to_string())?),
+        Err(_) => None,
+    };
+
+    let mut diff_opts = DiffOptions::new();
+    diff_opts.pathspec(&file_path).disable_pathspec_match(true);
+
+    let diff = repo
+        .diff_tree_to_index(head_tree.as_ref(), Some(&index), Some(&mut diff_opts))
+        .map_err(|e| e.to_string())?;
+
+    let delta = diff
+        .deltas()
+        .next()
+        .ok_or_else(|| format!("NotFound: '{}' has no staged changes", file_path))?;
+
+    Ok(delta_file_diff(&repo, &delta, false))
+}
+
+// Uncommitted changes split like GUI clients show them: HEAD to index and index to working tree
+#[tauri::command]
+pub fn get_working_diff(repo_path: String) -> Result<WorkingDiff, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let index = repo.index().map_err(|e| e.to_string())?;
+
+    // Unborn HEAD: everything in the index is staged
+    let head_tree = match repo.head() {
+        Ok(head) => Some(head.peel_to_tree().map_err(|e| e.to_string())?),
+        Err(_) => None,
+    };
+
+    let mut find_opts = DiffFindOptions::new();
+    find_opts.renames(true).copies(true);
+
+    let mut staged_diff = repo
+        .diff_tree_to_index(head_tree.as_ref(), Some(&index), None)
+        .map_err(|e| e.to_string())?;
+    staged_diff
+        .find_similar(Some(&mut find_opts))
+        .map_err(|e| e.to_string())?;
+
+    // Untracked files count as unstaged additions, as in the Uncommitted Changes node
+    let mut diff_opts = DiffOptions::new();
+    diff_opts
+        .include_untracked(true)
+        .recurse_untracked_dirs(true);
+    let mut unstaged_diff = repo
+        .diff_index_to_workdir(Some(&index), Some(&mut diff_opts))
+        .map_err(|e| e.to_string())?;
+    unstaged_diff
+        .find_similar(Some(&mut find_opts))
+        .map_err(|e| e.to_string())?;
+
+    Ok(WorkingDiff {
+        staged: staged_diff
+            .deltas()
+            .map(|delta| delta_file_diff(&repo, &delta, false))
+            .collect(),
+        unstaged: unstaged_diff
+            .deltas()
+            .map(|delta| delta_file_diff(&repo, &delta, true))
+            .collect(),
+    })
+}
+
+// Commits the staged changes on top of HEAD and returns the new commit's OID
+#[tauri::command]
+pub fn commit(repo_path: String, message: String) -> Result<String, String> {
+    let mut repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    if message.trim().is_empty() {
+        return Err("Commit message cannot be empty".to_string());
+    }
+
+    let mut index = repo.index().map_err(|e| e.to_string())?;
+    if index.has_conflicts() {
+        return Err("Conflict: resolve the conflicted files before committing".to_string());
+    }
+    let tree_oid = index.write_tree().map_err(|e| e.to_string())?;
+
+    // Concluding a merge records the merged commits as extra parents, like git commit does
+    let mut merge_heads = Vec::new();
+    if repo.state() == git2::RepositoryState::Merge {
+        repo.mergehead_foreach(|oid| {
+            merge_heads.push(*oid);
+            true
+        })
+        .map_err(|e| e.to_string())?;
+    }
+
+    // Unborn HEAD: this becomes the root commit of the branch
+    let head_commit = match repo.head() {
+        Ok(head) => Some(head.peel_to_commit().map_err(|e| e.to_string())?),
+        Err(_) => None,
+    };
+
+    let unchanged = match &head_commit {
+        Some(head_commit) => head_commit.tree_id() == tree_oid,
+        None => index.is_empty(),
+    };
+    if unchanged && merge_heads.is_empty() {
+        return Err("NothingToCommit: There are no staged changes".to_string());
+    }
+
+    let signature = default_signature(&repo)?;
+    let tree = repo.find_tree(tree_oid).map_err(|e| e.to_string())?;
+    let mut parents: Vec<git2::Commit> = head_commit.into_iter().collect();
+    for oid in merge_heads {
+        parents.push(repo.find_commit(oid).map_err(|e| e.to_string())?);
+    }
+    let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+
+    let oid = repo
+        .commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            &message,
+            &tree,
+            &parent_refs,
+        )
+        .map_err(|e| format!("Failed to commit: {}", e))?;
+
+    if parents.len() > 1 {
+        repo.cleanup_state().map_err(|e| e.to_string())?;
+    }
+
+    Ok(oid.to_string())
+}
+
+// Helper function to load both sides of a delta into a FileDiff.
+// With `new_from_workdir` the new side is read from disk, since working tree files aren't blobs yet.
+fn delta_file_diff(repo: &Repository, delta: &git2::DiffDelta, new_from_workdir: bool) -> FileDiff {
+    let path = delta
+        .new_file()
+        .path()
+        .or_else(|| delta.old_file().path())
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let status = match delta.status() {
+        Delta::Untracked => Delta::Added,
+        status => status,
+    };
+    let mut file = FileDiff {
+        old_path: delta_old_path(delta, &path),
+        path,
+        old_content: String::new(),
+        new_content: String::new(),
+        status: delta_status_name(status).to_string(),
+        old_content_base64: None,
+        new_content_base64: None,
+        binary: false,
+        old_size: None,
+        new_size: None,
+    };
+    let workdir_bytes = classify_delta_content(repo, delta, new_from_workdir, &mut file);
+    if file.binary {
+        return file;
+    }
+
+    file.old_content = diff_file_content(repo, &delta.old_file());
+    file.new_content = match &workdir_bytes {
+        Some(bytes) => String::from_utf8_lossy(bytes).to_string(),
+        // A file deleted from the working tree has nothing to read
+        None if new_from_workdir => String::new(),
+        None => diff_file_content(repo, &delta.new_file()),
+    };
+    file
+}
+
+// Helper function to set the binary flag and sizes of a diffed file. With `new_from_workdir` the
+// new side isn't a blob yet and is read from disk; its bytes are returned so the caller can reuse them.
+fn classify_delta_content(
+    repo: &Repository,
+    delta: &git2::DiffDelta,
+    new_from_workdir: bool,
+    file: &mut FileDiff,
+) -> Option<Vec<u8>> {
+    let workdir_bytes = if new_from_workdir {
+        delta
+            .new_file()
+            .path()
+            .and_then(|path| read_workdir_file(repo, path))
+    } else {
+        None
+    };
+
+    // Lossy UTF-8 of binary content is garbage, so only the sizes are sent
+    let new_binary = match &workdir_bytes {
+        Some(bytes) => looks_binary(bytes),
+        None => is_binary_blob(repo, &delta.new_file()),
+    };
+    file.binary = is_binary_blob(repo, &delta.old_file()) || new_binary;
+    if file.binary {
+        file.old_size = Some(blob_size(repo, &delta.old_file()));
+        file.new_size = Some(match &workdir_bytes {
+            Some(bytes) => bytes.len() as u64,
+            None => blob_size(repo, &delta.new_file()),
+        });
+    }
+
+    workdir_bytes
+}
+
+// Contents of `.git/description` (used by gitweb), empty when the file doesn't exist
+#[tauri::command]
+pub fn get_repo_description(repo_path: String) -> Result<String, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    match fs::read_to_string(repo.path().join("description")) {
+        Ok(content) => Ok(content.trim_end().to_string()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(description_error("read", e)),
+    }
+}
+
+#[tauri::command]
+pub fn set_repo_description(repo_path: String, text: String) -> Result<(), String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    fs::write(
+        repo.path().join("description"),
+        format!("{}\n", text.trim_end()),
+    )
+    .map_err(|e| description_error("write", e))
+}
+
+// Helper function to describe a failure to access the description file
+fn description_error(action: &str, e: std::io::Error) -> String {
+    if e.kind() == std::io::ErrorKind::PermissionDenied {
+        format!(
+            "PermissionDenied: not allowed to {} the repository description",
+            action
+        )
+    } else {
+        format!("Failed to {} the repository description: {}", action, e)
+    }
+}
+
+// Whether `path` (absolute, or relative to the working tree) is ignored by .gitignore rules
+#[tauri::command]
+pub fn is_path_ignored(repo_path: String, path: String) -> Result<bool, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let relative = workdir_relative_path(&repo, &path)?;
+
+    repo.is_path_ignored(&relative).map_err(|e| e.to_string())
+}
+
+// Stages `path` even when it is ignored, like `git add -f`
+#[tauri::command]
+pub fn force_add_path(repo_path: String, path: String) -> Result<(), String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let relative = workdir_relative_path(&repo, &path)?;
+
+    let full_path = repo
+        .workdir()
+        .ok_or_else(|| "Repository has no working directory".to_string())?
+        .join(&relative);
+    if !full_path.is_file() {
+        return Err(format!(
+            "NotFound: '{}' is not a file in the working tree",
+            path
+        ));
+    }
+
+    // Unlike add_all, add_path doesn't consult the ignore rules
+    let mut index = repo.index().map_err(|e| e.to_string())?;
+    index
+        .add_path(&relative)
+        .map_err(|e| format!("Failed to add '{}': {}", path, e))?;
+    index.write().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// Stages one changed path (`git add`), including deletions and untracked files
+#[tauri::command]
+pub fn stage_file(repo_path: String, path: String) -> Result<(), String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let relative = workdir_relative_path(&repo, &path)?;
+    let full_path = repo
+        .workdir()
+        .ok_or_else(|| "Repository has no working directory".to_string())?
+        .join(&relative);
+
+    let mut index = repo.index().map_err(|e| e.to_string())?;
+    let tracked = index.get_path(&relative, 0).is_some();
+
+    if tracked && !full_path.exists() {
+        // Deleted in the working tree: stage the removal
+        index
+            .remove_path(&relative)
+            .map_err(|e| format!("Failed to stage removal of '{}': {}", path, e))?;
+    } else if tracked {
+        index
+            .add_path(&relative)
+            .map_err(|e| format!("Failed to stage '{}': {}", path, e))?;
+    } else if full_path.exists() {
+        // add_all respects the ignore rules and handles untracked directories
+        if repo.is_path_ignored(&relative).unwrap_or(false) {
+            return Err(format!(
+                "'{}' is ignored. Use force_add_path to stage it anyway",
+                path
+            ));
+        }
+        index
+            .add_all([&relative], git2::IndexAddOption::DEFAULT, None)
+            .map_err(|e| format!("Failed to stage '{}': {}", path, e))?;
+    } else {
+        return Err(format!("NotFound: '{}' has no changes to stage", path));
+    }
+    index.write().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// Resets the index entry of one path to HEAD (`git reset -- <path>`), keeping the working tree
+#[tauri::command]
+pub fn unstage_file(repo_path: String, path: String) -> Result<(), String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let relative = workdir_relative_path(&repo, &path)?;
+
+    match repo.head().and_then(|head| head.peel_to_commit()) {
+        Ok(head_commit) => repo
+            .reset_default(Some(head_commit.as_object()), [&relative])
+            .map_err(|e| format!("Failed to unstage '{}': {}", path, e))?,
+        Err(_) => {
+            // Unborn HEAD: there's nothing to reset to, so the entry is removed
+            let mut index = repo.index().map_err(|e| e.to_string())?;
+            index
+                .remove_path(&relative)
+                .map_err(|e| format!("Failed to unstage '{}': {}", path, e))?;
+            index.write().map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(())
+}
+
+// Helper function to turn a user-supplied path into one relative to the working tree,
+// rejecting anything that points outside of it
+fn workdir_relative_path(repo: &Repository, path: &str) -> Result<std::path::PathBuf, String> {
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| "Repository has no working directory".to_string())?;
+    let outside = || format!("OutsideRepository: '{}' is not inside the repository", path);
+
+    let path = std::path::Path::new(path);
+    let relative = if path.is_absolute() {
+        match path.strip_prefix(workdir) {
+            Ok(relative) => relative.to_path_buf(),
+            // Symlinked locations (e.g. /tmp on macOS) only match once both sides are resolved
+            Err(_) => {
+                let workdir = workdir.canonicalize().map_err(|e| e.to_string())?;
+                let path = path.canonicalize().map_err(|_| outside())?;
+                path.strip_prefix(&workdir)
+                    .map_err(|_| outside())?
+                    .to_path_buf()
+            }
+        }
+    } else {
+        path.to_path_buf()
+    };
+    // "./src" means the same as "src"
+    let relative: std::path::PathBuf = relative
+        .components()
+        .filter(|c| *c != std::path::Component::CurDir)
+        .collect();
+
+    if relative.as_os_str().is_empty()
+        || relative
+            .components()
+            .any(|c| !matches!(c, std::path::Component::Normal(_)))
+    {
+        return Err(outside());
+    }
+    Ok(relative)
+}
+
+// Helper function to format a relative path the way git pathspecs expect it, with '/' separators
+fn pathspec_string(path: &std::path::Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// Images larger than this are not sent for previews
+const MAX_IMAGE_PREVIEW_BYTES: usize = 5 * 1024 * 1024;
+
+// Helper function to check whether a path is an image the UI can preview
+fn is_image_path(path: &str) -> bool {
+    let extension = std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase());
+    matches!(
+        extension.as_deref(),
+        Some("png" | "jpg" | "jpeg" | "gif" | "webp" | "bmp" | "ico" | "avif")
+    )
+}
+
+// Helper function to base64-encode one side of a delta, None if it doesn't exist or is too big
+fn image_base64(repo: &Repository, file: &git2::DiffFile) -> Option<String> {
+    use base64::Engine;
+
+    if file.id().is_zero() {
+        return None;
+    }
+
+    // Check the size from the header before loading the whole blob
+    let odb = repo.odb().ok()?;
+    let (size, _) = odb.read_header(file.id()).ok()?;
+    if size > MAX_IMAGE_PREVIEW_BYTES {
+        return None;
+    }
+
+    let blob = repo.find_blob(file.id()).ok()?;
+    Some(base64::engine::general_purpose::STANDARD.encode(blob.content()))
+}
+
+// Helper function to encode a working copy image for previews, with the same size limit as blobs
+fn workdir_image_base64(bytes: &[u8]) -> Option<String> {
+    use base64::Engine;
+
+    if bytes.len() > MAX_IMAGE_PREVIEW_BYTES {
+        return None;
+    }
+    Some(base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+// Helper function to read a file from the working directory, None if it doesn't exist there
+fn read_workdir_file(repo: &Repository, path: &std::path::Path) -> Option<Vec<u8>> {
+    let workdir = repo.workdir()?;
+    fs::read(workdir.join(path)).ok()
+}
+
+// Helper function to apply git's binary heuristic to raw bytes: a NUL in the first 8000 bytes
+fn looks_binary(bytes: &[u8]) -> bool {
+    bytes.iter().take(8000).any(|&b| b == 0)
+}
+
+// Helper function to check a diff side for binary content, using git's NUL byte heuristic
+fn is_binary_blob(repo: &Repository, file: &git2::DiffFile) -> bool {
+    if file.id().is_zero() {
+        return false;
+    }
+    repo.find_blob(file.id())
+        .map(|blob| blob.is_binary())
+        .unwrap_or(false)
+}
+
+// Helper function to get the size of a diff side without loading its content
+fn blob_size(repo: &Repository, file: &git2::DiffFile) -> u64 {
+    if file.id().is_zero() {
+        return 0;
+    }
+    repo.odb()
+        .and_then(|odb| odb.read_header(file.id()))
+        .map(|(size, _)| size as u64)
+        .unwrap_or(0)
+}
+
+// Helper function to read the blob behind one side of a delta, empty if that side doesn't exist
+fn diff_file_content(repo: &Repository, file: &git2::DiffFile) -> String {
+    if file.id().is_zero() {
+        return String::new();
+    }
+    read_blob_string(repo, file.id()).unwrap_or_default()
+}
+
+// Helper function to get the source path of a rename or copy found by find_similar
+fn delta_old_path(delta: &git2::DiffDelta, path: &str) -> Option<String> {
+    match delta.status() {
+        Delta::Renamed | Delta::Copied => delta
+            .old_file()
+            .path()
+            .map(|p| p.to_string_lossy().to_string())
+            .filter(|old_path| old_path != path),
+        _ => None,
+    }
+}
+
+// Helper function to order files by status when sorting a diff: modified first, deleted last
+fn status_rank(status: Delta) -> u8 {
+    match status {
+        Delta::Modified => 0,
+        Delta::Added => 1,
+        Delta::Renamed => 2,
+        Delta::Copied => 3,
+        Delta::Typechange => 4,
+        Delta::Deleted => 5,
+        _ => 6,
+    }
+}
+
+fn get_file_content(
+    repo: &Repository,
+    tree: &git2::Tree,
+    file_path: &str,
+    entry_path: Option<&std::path::Path>,
+) -> String {
+    let path_to_find = entry_path.unwrap_or_else(|| std::path::Path::new(file_path));
+
+    // Try to find the file in the tree
+    let tree_entry = match tree.get_path(path_to_find) {
+        Ok(entry) => entry,
+        Err(e) => {
+            log::error!("Failed to get path {:?} from tree: {}", path_to_find, e);
+            return String::new();
+        }
+    };
+
+    if tree_entry.kind() != Some(ObjectType::Blob) {
+        log::error!(
+            "Entry {:?} is not a blob, it's a {:?}",
+            path_to_find,
+            tree_entry.kind()
+        );
+        return String::new();
+    }
+
+    let obj = match repo.find_object(tree_entry.id(), Some(ObjectType::Blob)) {
+        Ok(obj) => obj,
+        Err(e) => {
+            log::error!("Failed to find object {:?}: {}", tree_entry.id(), e);
+            return String::new();
+        }
+    };
+
+    let blob = obj.as_blob().unwrap();
+    String::from_utf8_lossy(blob.content()).to_string()
+}
+
+#[tauri::command]
+pub fn get_blob_oid(
+    repo_path: String,
+    commit_id: String,
+    file_path: String,
+) -> Result<Option<String>, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    let oid = Oid::from_str(&commit_id)
+        .map_err(|e| format!("Invalid commit ID '{}': {}", commit_id, e))?;
+    let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+    let tree = commit.tree().map_err(|e| e.to_string())?;
+
+    // A missing path is not an error, the file just doesn't exist at this commit
+    let entry = match tree.get_path(std::path::Path::new(&file_path)) {
+        Ok(entry) => entry,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+
+    if entry.kind() != Some(ObjectType::Blob) {
+        return Ok(None);
+    }
+
+    Ok(Some(entry.id().to_string()))
+}
+
+#[tauri::command]
+pub fn get_commit_tree_oid(repo_path: String, commit_id: String) -> Result<String, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    let oid = Oid::from_str(&commit_id)
+        .map_err(|e| format!("Invalid commit ID '{}': {}", commit_id, e))?;
+    let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+
+    // Commits with the same tree have identical content, so diffs can be cached per tree pair
+    Ok(commit.tree_id().to_string())
+}
+
+// File counts keyed by tree OID. Trees are content-addressed, so entries never go stale
+static TREE_FILE_COUNT_CACHE: std::sync::OnceLock<std::sync::Mutex<HashMap<Oid, usize>>> =
+    std::sync::OnceLock::new();
+
+#[tauri::command]
+pub fn get_tree_file_count(repo_path: String, commit_id: String) -> Result<usize, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    let oid = Oid::from_str(&commit_id)
+        .map_err(|e| format!("Invalid commit ID '{}': {}", commit_id, e))?;
+    let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+
+    // Many commits share a tree (e.g. merges without changes, reverts)
+    let cache = TREE_FILE_COUNT_CACHE.get_or_init(Default::default);
+    if let Some(count) = cache.lock().unwrap().get(&commit.tree_id()) {
+        return Ok(*count);
+    }
+
+    let tree = commit.tree().map_err(|e| e.to_string())?;
+    let mut count = 0;
+    tree.walk(git2::TreeWalkMode::PreOrder, |_, entry| {
+        if entry.kind() == Some(ObjectType::Blob) {
+            count += 1;
+        }
+        git2::TreeWalkResult::Ok
+    })
+    .map_err(|e| e.to_string())?;
+
+    cache.lock().unwrap().insert(tree.id(), count);
+
+    Ok(count)
+}
+
+#[tauri::command]
+pub fn get_three_way_diff(repo_path: String, file_path: String) -> Result<ThreeWayDiff, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let index = repo.index().map_err(|e| e.to_string())?;
+
+    let conflict = find_conflict(&index, &file_path)?;
+
+    let read_side = |entry: Option<git2::IndexEntry>| -> Result<Option<String>, String> {
+        match entry {
+            Some(entry) => read_blob_string(&repo, entry.id).map(Some),
+            None => Ok(None),
+        }
+    };
+
+    Ok(ThreeWayDiff {
+        path: file_path.clone(),
+        base: read_side(conflict.ancestor)?,
+        ours: read_side(conflict.our)?,
+        theirs: read_side(conflict.their)?,
+    })
+}
+
+#[tauri::command]
+pub fn resolve_conflict_with(
+    repo_path: String,
+    file_path: String,
+    side: String,
+) -> Result<(), String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| "Repository has no working directory".to_string())?
+        .to_path_buf();
+    let mut index = repo.index().map_err(|e| e.to_string())?;
+
+    let conflict = find_conflict(&index, &file_path)?;
+
+    // None means the chosen side deleted the file
+    let content: Option<Vec<u8>> = match side.as_str() {
+        "ours" => match &conflict.our {
+            Some(entry) => Some(read_blob_bytes(&repo, entry.id)?),
+            None => None,
+        },
+        "theirs" => match &conflict.their {
+            Some(entry) => Some(read_blob_bytes(&repo, entry.id)?),
+            None => None,
+        },
+        "union" => {
+            let (ours, theirs) = match (&conflict.our, &conflict.their) {
+                (Some(ours), Some(theirs)) => (ours, theirs),
+                _ => {
+                    return Err(format!(
+                        "Cannot union-merge '{}': the file was deleted on one side",
+                        file_path
+                    ))
+                }
+            };
+
+            // Both sides added the file: merge against an empty ancestor
+            let empty_ancestor;
+            let ancestor = match &conflict.ancestor {
+                Some(ancestor) => ancestor,
+                None => {
+                    empty_ancestor = git2::IndexEntry {
+                        ctime: ours.ctime,
+                        mtime: ours.mtime,
+                        dev: 0,
+                        ino: 0,
+                        mode: ours.mode,
+                        uid: 0,
+                        gid: 0,
+                        file_size: 0,
+                        id: repo.blob(&[]).map_err(|e| e.to_string())?,
+                        flags: 0,
+                        flags_extended: 0,
+                        path: ours.path.clone(),
+                    };
+                    &empty_ancestor
+                }
+            };
+
+            let mut merge_opts = git2::MergeFileOptions::new();
+            merge_opts.favor(git2::FileFavor::Union);
+            let result = repo
+                .merge_file_from_index(ancestor, ours, theirs, Some(&mut merge_opts))
+                .map_err(|e| format!("Failed to union-merge '{}': {}", file_path, e))?;
+            Some(result.content().to_vec())
+        }
+        _ => {
+            return Err(format!(
+                "Invalid side '{}'. Expected \"ours\", \"theirs\" or \"union\"",
+                side
+            ))
+        }
+    };
+
+    let full_path = workdir.join(&file_path);
+    let relative_path = std::path::Path::new(&file_path);
+
+    // Adding or removing by path also clears the conflict entries from the index
+    match content {
+        Some(content) => {
+            if let Some(parent) = full_path.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            fs::write(&full_path, content)
+                .map_err(|e| format!("Failed to write '{}': {}", file_path, e))?;
+            index
+                .add_path(relative_path)
+                .map_err(|e| format!("Failed to stage '{}': {}", file_path, e))?;
+        }
+        None => {
+            if full_path.exists() {
+                fs::remove_file(&full_path)
+                    .map_err(|e| format!("Failed to remove '{}': {}", file_path, e))?;
+            }
+            index
+                .remove_path(relative_path)
+                .map_err(|e| format!("Failed to stage removal of '{}': {}", file_path, e))?;
+        }
+    }
+
+    index.write().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_merge_status(repo_path: String) -> Result<MergeStatus, String> {
+    let mut repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    if repo.state() != git2::RepositoryState::Merge {
+        return Ok(MergeStatus {
+            merging: false,
+            merge_heads: vec![],
+            merge_source: None,
+            conflicted: vec![],
+            resolved: vec![],
+        });
+    }
+
+    let mut merge_head_oids = Vec::new();
+    repo.mergehead_foreach(|oid| {
+        merge_head_oids.push(*oid);
+        true
+    })
+    .map_err(|e| format!("Failed to read MERGE_HEAD: {}", e))?;
+
+    // Name the merge source after a local or remote branch at the first merge head
+    let merge_source = merge_head_oids.first().and_then(|merge_oid| {
+        repo.branches(None).ok()?.flatten().find_map(|(branch, _)| {
+            if branch.get().target() == Some(*merge_oid) {
+                branch.name().ok().flatten().map(|name| name.to_string())
+            } else {
+                None
+            }
+        })
+    });
+
+    let index = repo.index().map_err(|e| e.to_string())?;
+
+    let mut conflicted = Vec::new();
+    for conflict in index.conflicts().map_err(|e| e.to_string())? {
+        let conflict = conflict.map_err(|e| e.to_string())?;
+        if let Some(entry) = conflict.our.or(conflict.their).or(conflict.ancestor) {
+            conflicted.push(String::from_utf8_lossy(&entry.path).to_string());
+        }
+    }
+
+    let head_tree = repo
+        .head()
+        .and_then(|h| h.peel_to_tree())
+        .map_err(|e| format!("Failed to resolve HEAD: {}", e))?;
+    let diff = repo
+        .diff_tree_to_index(Some(&head_tree), Some(&index), None)
+        .map_err(|e| e.to_string())?;
+
+    let resolved = diff
+        .deltas()
+        .filter(|delta| delta.status() != Delta::Conflicted)
+        .filter_map(|delta| {
+            delta
+                .new_file()
+                .path()
+                .or_else(|| delta.old_file().path())
+                .map(|p| p.to_string_lossy().to_string())
+        })
+        .filter(|path| !conflicted.contains(path))
+        .collect();
+
+    Ok(MergeStatus {
+        merging: true,
+        merge_heads: merge_head_oids.iter().map(|oid| oid.to_string()).collect(),
+        merge_source,
+        conflicted,
+        resolved,
+    })
+}
+
+// Reads the progress of an in-progress `git rebase` from its state directory
+#[tauri::command]
+pub fn get_rebase_progress(repo_path: String) -> Result<RebaseProgress, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    // Git's CLI doesn't write the cmt.N files libgit2's Rebase API needs to reopen a
+    // rebase, so the state files are read directly, the same way `git status` does
+    let state = repo.state();
+    let merge_backend = matches!(
+        state,
+        git2::RepositoryState::RebaseMerge | git2::RepositoryState::RebaseInteractive
+    );
+    let apply_backend = matches!(
+        state,
+        git2::RepositoryState::Rebase | git2::RepositoryState::ApplyMailboxOrRebase
+    );
+    if !merge_backend && !apply_backend {
+        return Ok(RebaseProgress {
+            rebasing: false,
+            interactive: false,
+            step: None,
+            total: None,
+            current_commit: None,
+            onto: None,
+            branch: None,
+        });
+    }
+
+    let state_dir_name = if merge_backend {
+        "rebase-merge"
+    } else {
+        "rebase-apply"
+    };
+    let state_dir = repo.path().join(state_dir_name);
+    let read_state = |file: &str| {
+        fs::read_to_string(state_dir.join(file))
+            .ok()
+            .map(|content| content.trim().to_string())
+            .filter(|content| !content.is_empty())
+    };
+
+    let (step, total) = if merge_backend {
+        (read_state("msgnum"), read_state("end"))
+    } else {
+        (read_state("next"), read_state("last"))
+    };
+
+    let current_commit = if merge_backend {
+        // stopped-sha only exists while stopped, otherwise use the last entry of the done list.
+        // Rebases started through libgit2 record the step in `current` instead
+        let last_done = || {
+            read_state("done")?
+                .lines()
+                .rev()
+                .map(str::trim)
+                .find(|line| !line.is_empty() && !line.starts_with('#'))
+                .and_then(|line| line.split_whitespace().nth(1))
+                .map(|id| id.to_string())
+        };
+        read_state("stopped-sha")
+            .or_else(|| read_state("current"))
+            .or_else(last_done)
+    } else {
+        read_state("original-commit")
+    }
+    // The todo list uses abbreviated IDs
+    .and_then(|id| {
+        repo.revparse_single(&id)
+            .and_then(|obj| obj.peel_to_commit())
+            .ok()
+            .map(|commit| commit.id().to_string())
+    });
+
+    let branch = read_state("head-name")
+        .filter(|name| name != "detached HEAD")
+        .map(|name| {
+            name.strip_prefix("refs/heads/")
+                .unwrap_or(&name)
+                .to_string()
+        });
+
+    Ok(RebaseProgress {
+        rebasing: true,
+        interactive: state == git2::RepositoryState::RebaseInteractive,
+        step: step.and_then(|step| step.parse().ok()),
+        total: total.and_then(|total| total.parse().ok()),
+        current_commit,
+        onto: read_state("onto"),
+        branch,
+    })
+}
+
+// Commits the resolved current step of an open rebase and applies the remaining ones,
+// stopping again at the next conflict
+#[tauri::command]
+pub fn rebase_continue(repo_path: String) -> Result<RebaseResult, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let committer = default_signature(&repo)?;
+
+    // Only rebases libgit2 can reopen are supported, which excludes `git rebase -i`
+    let mut rebase = repo
+        .open_rebase(None)
+        .map_err(|e| format!("Cannot continue the rebase: {}", e))?;
+
+    let commit_step = |rebase: &mut git2::Rebase| -> Result<Option<RebaseResult>, String> {
+        let index = repo.index().map_err(|e| e.to_string())?;
+        if index.has_conflicts() {
+            let stopped_at = rebase
+                .operation_current()
+                .and_then(|idx| rebase.nth(idx))
+                .map(|operation| operation.id().to_string());
+            return Ok(Some(RebaseResult {
+                finished: false,
+                conflicts: conflicted_paths(&index)?,
+                stopped_at,
+                head: None,
+            }));
+        }
+
+        match rebase.commit(None, &committer, None) {
+            Ok(_) => Ok(None),
+            // The step's changes are already upstream, skip it like git does
+            Err(e) if e.code() == git2::ErrorCode::Applied => Ok(None),
+            Err(e) => Err(format!("Failed to commit rebase step: {}", e)),
+        }
+    };
+
+    if rebase.operation_current().is_some() {
+        if let Some(result) = commit_step(&mut rebase)? {
+            return Ok(result);
+        }
+    }
+
+    while let Some(operation) = rebase.next() {
+        operation.map_err(|e| format!("Failed to apply rebase step: {}", e))?;
+        if let Some(result) = commit_step(&mut rebase)? {
+            return Ok(result);
+        }
+    }
+
+    rebase
+        .finish(Some(&committer))
+        .map_err(|e| format!("Failed to finish rebase: {}", e))?;
+
+    let head = repo
+        .head()
+        .ok()
+        .and_then(|head| head.target())
+        .map(|oid| oid.to_string());
+    Ok(RebaseResult {
+        finished: true,
+        conflicts: vec![],
+        stopped_at: None,
+        head,
+    })
+}
+
+// Helper function to find the conflict entries of a path in the index
+fn find_conflict(index: &git2::Index, file_path: &str) -> Result<git2::IndexConflict, String> {
+    let conflicts = index.conflicts().map_err(|e| e.to_string())?;
+
+    for conflict in conflicts {
+        let conflict = conflict.map_err(|e| e.to_string())?;
+        let path = [&conflict.our, &conflict.their, &conflict.ancestor]
+            .into_iter()
+            .flatten()
+            .next()
+            .map(|entry| String::from_utf8_lossy(&entry.path).to_string());
+
+        if path.as_deref() == Some(file_path) {
+            return Ok(conflict);
+        }
+    }
+
+    Err(format!("'{}' is not in conflict", file_path))
+}
+
+// Helper function to read the raw bytes of a blob
+fn read_blob_bytes(repo: &Repository, oid: Oid) -> Result<Vec<u8>, String> {
+    let blob = repo
+        .find_blob(oid)
+        .map_err(|e| format!("Failed to find blob {}: {}", oid, e))?;
+    Ok(blob.content().to_vec())
+}
+
+// Helper function to read a blob as (lossy) UTF-8 text
+fn read_blob_string(repo: &Repository, oid: Oid) -> Result<String, String> {
+    read_blob_bytes(repo, oid).map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+}
+
+// Helper function to reject an export target inside the working tree or the .git directory
+fn ensure_outside_repository(repo: &Repository, target: &std::path::Path) -> Result<(), String> {
+    // The target usually doesn't exist yet, so resolve its deepest existing ancestor instead
+    let absolute = std::path::absolute(target).map_err(|e| e.to_string())?;
+    let mut existing = absolute.as_path();
+    let mut missing = Vec::new();
+    while !existing.exists() {
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                existing = parent;
+            }
+            _ => break,
+        }
+    }
+    let mut resolved = existing
+        .canonicalize()
+        .map_err(|e| format!("Invalid output path '{}': {}", target.display(), e))?;
+    resolved.extend(missing.iter().rev());
+
+    for root in [repo.workdir(), Some(repo.path())].into_iter().flatten() {
+        if root
+            .canonicalize()
+            .is_ok_and(|root| resolved.starts_with(root))
+        {
+            return Err(format!(
+                "InsideRepository: '{}' is inside the repository",
+                target.display()
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn materialize_tree(
+    repo_path: String,
+    commit_id: String,
+    out_dir: String,
+    raw_tree: Option<bool>,
+) -> Result<(), String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    let oid = Oid::from_str(&commit_id)
+        .map_err(|e| format!("Invalid commit ID '{}': {}", commit_id, e))?;
+    let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+    let tree = commit.tree().map_err(|e| e.to_string())?;
+
+    let out_dir = std::path::PathBuf::from(&out_dir);
+    ensure_outside_repository(&repo, &out_dir)?;
+    if out_dir.exists() {
+        let is_empty = fs::read_dir(&out_dir)
+            .map_err(|e| format!("Failed to read output directory: {}", e))?
+            .next()
+            .is_none();
+        if !is_empty {
+            return Err(format!(
+                "Output directory '{}' is not empty",
+                out_dir.display()
+            ));
+        }
+    }
+    fs::create_dir_all(&out_dir)
+        .map_err(|e| format!("Failed to create output directory: {}", e))?;
+
+    // Like `git archive`, skip export-ignore paths unless the raw tree was requested
+    let export_ignore = if raw_tree.unwrap_or(false) {
+        ExportIgnore::default()
+    } else {
+        ExportIgnore::from_tree(&repo, &tree)?
+    };
+
+    for_each_tree_blob(&tree, |path, entry| {
+        if export_ignore.is_ignored(path) {
+            return Ok(());
+        }
+
+        let target = out_dir.join(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+
+        let blob = repo.find_blob(entry.id()).map_err(|e| e.to_string())?;
+        write_blob_file(&target, blob.content(), entry.filemode())
+            .map_err(|e| format!("Failed to write '{}': {}", path, e))
+    })
+}
+
+#[tauri::command]
+pub fn archive_commit(
+    repo_path: String,
+    commit_id: String,
+    out_path: String,
+    format: String,
+    raw_tree: Option<bool>,
+) -> Result<(), String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    if format != "tar" && format != "zip" {
+        return Err(format!(
+            "Invalid archive format '{}'. Expected \"tar\" or \"zip\"",
+            format
+        ));
+    }
+
+    let oid = Oid::from_str(&commit_id)
+        .map_err(|e| format!("Invalid commit ID '{}': {}", commit_id, e))?;
+    let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+    let tree = commit.tree().map_err(|e| e.to_string())?;
+    // Like `git archive`, skip export-ignore paths unless the raw tree was requested
+    let export_ignore = if raw_tree.unwrap_or(false) {
+        ExportIgnore::default()
+    } else {
+        ExportIgnore::from_tree(&repo, &tree)?
+    };
+
+    ensure_outside_repository(&repo, std::path::Path::new(&out_path))?;
+    let file = fs::File::create(&out_path)
+        .map_err(|e| format!("Failed to create '{}': {}", out_path, e))?;
+    let writer = std::io::BufWriter::new(file);
+
+    // Entries are written one blob at a time as the tree is walked
+    let mtime = commit.time().seconds().max(0) as u64;
+    let is_link = |mode: i32| mode == i32::from(git2::FileMode::Link);
+    let permissions = |mode: i32| {
+        if mode == i32::from(git2::FileMode::BlobExecutable) {
+            0o755
+        } else {
+            0o644
+        }
+    };
+
+    if format == "tar" {
+        let mut builder = tar::Builder::new(writer);
+
+        for_each_tree_blob(&tree, |path, entry| {
+            if export_ignore.is_ignored(path) {
+                return Ok(());
+            }
+            let blob = repo.find_blob(entry.id()).map_err(|e| e.to_string())?;
+
+            let mut header = tar::Header::new_gnu();
+            header.set_mtime(mtime);
+            let result = if is_link(entry.filemode()) {
+                header.set_entry_type(tar::EntryType::Symlink);
+                header.set_size(0);
+                header.set_mode(0o777);
+                let link_target = String::from_utf8_lossy(blob.content()).to_string();
+                builder.append_link(&mut header, path, link_target)
+            } else {
+                header.set_entry_type(tar::EntryType::Regular);
+                header.set_size(blob.content().len() as u64);
+                header.set_mode(permissions(entry.filemode()));
+                builder.append_data(&mut header, path, blob.content())
+            };
+            result.map_err(|e| format!("Failed to add '{}' to archive: {}", path, e))
+        })?;
+
+        builder
+            .into_inner()
+            .and_then(|mut writer| writer.flush())
+            .map_err(|e| format!("Failed to finish archive: {}", e))?;
+    } else {
+        let mut zip = zip::ZipWriter::new(writer);
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+
+        for_each_tree_blob(&tree, |path, entry| {
+            if export_ignore.is_ignored(path) {
+                return Ok(());
+            }
+            let blob = repo.find_blob(entry.id()).map_err(|e| e.to_string())?;
+
+            let result = if is_link(entry.filemode()) {
+                let link_target = String::from_utf8_lossy(blob.content()).to_string();
+                zip.add_symlink(path, link_target, options)
+                    .map_err(|e| e.to_string())
+            } else {
+                let file_options = options.unix_permissions(permissions(entry.filemode()));
+                zip.start_file(path, file_options)
+                    .map_err(|e| e.to_string())
+                    .and_then(|()| zip.write_all(blob.content()).map_err(|e| e.to_string()))
+            };
+            result.map_err(|e| format!("Failed to add '{}' to archive: {}", path, e))
+        })?;
+
+        zip.finish()
+            .map_err(|e| e.to_string())
+            .and_then(|mut writer| writer.flush().map_err(|e| e.to_string()))
+            .map_err(|e| format!("Failed to finish archive: {}", e))?;
+    }
+
+    Ok(())
+}
+
+// `export-ignore` rules collected from the .gitattributes files of an exported tree.
+// git2 can only look up attributes from the working tree or index, but `git archive` uses the
+// .gitattributes inside the archived commit, so the rules are parsed from the tree itself.
+#[derive(Default)]
+struct ExportIgnore {
+    rules: Vec<ExportIgnoreRule>,
+}
+
+struct ExportIgnoreRule {
+    base: String, // directory of the .gitattributes file, "" or ending with '/'
+    pattern: glob::Pattern,
+    basename_only: bool, // patterns without a slash match the file name at any depth
+    ignore: bool,        // false for `-export-ignore` / `!export-ignore`
+}
+
+impl ExportIgnore {
+    fn from_tree(repo: &Repository, tree: &git2::Tree) -> Result<Self, String> {
+        let mut files: Vec<(String, String)> = Vec::new();
+
+        for_each_tree_blob(tree, |path, entry| {
+            if entry.name() == Some(".gitattributes") {
+                let base = path.trim_end_matches(".gitattributes").to_string();
+                files.push((base, read_blob_string(repo, entry.id())?));
+            }
+            Ok(())
+        })?;
+
+        // Deeper files take precedence over shallower ones, info/attributes over everything
+        files.sort_by_key(|(base, _)| base.matches('/').count());
+        if let Ok(content) = fs::read_to_string(repo.path().join("info").join("attributes")) {
+            files.push((String::new(), content));
+        }
+
+        let mut rules = Vec::new();
+        for (base, content) in files {
+            for line in content.lines() {
+                if let Some(rule) = ExportIgnoreRule::parse(&base, line) {
+                    rules.push(rule);
+                }
+            }
+        }
+
+        Ok(ExportIgnore { rules })
+    }
+
+    // A path is ignored when it or any of its parent directories is export-ignored
+    fn is_ignored(&self, path: &str) -> bool {
+        if self.rules.is_empty() {
+            return false;
+        }
+
+        let mut candidates: Vec<&str> = path.match_indices('/').map(|(i, _)| &path[..i]).collect();
+        candidates.push(path);
+
+        candidates.into_iter().any(|candidate| {
+            // Later rules override earlier ones, like in .gitattributes
+            self.rules
+                .iter()
+                .rev()
+                .find(|rule| rule.matches(candidate))
+                .is_some_and(|rule| rule.ignore)
+        })
+    }
+}
+
+impl ExportIgnoreRule {
+    fn parse(base: &str, line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let mut parts = line.split_whitespace();
+        let pattern = parts.next()?;
+        let ignore = parts.find_map(|attr| match attr {
+            "export-ignore" => Some(true),
+            "-export-ignore" | "!export-ignore" => Some(false),
+            _ => None,
+        })?;
+
+        let basename_only = !pattern.trim_end_matches('/').contains('/');
+        let pattern = pattern.trim_start_matches('/').trim_end_matches('/');
+
+        // Malformed patterns are ignored, like git does
+        let pattern = glob::Pattern::new(pattern).ok()?;
+
+        Some(ExportIgnoreRule {
+            base: base.to_string(),
+            pattern,
+            basename_only,
+            ignore,
+        })
+    }
+
+    fn matches(&self, path: &str) -> bool {
+        let Some(relative) = path.strip_prefix(&self.base) else {
+            return false;
+        };
+
+        if self.basename_only {
+            let name = relative.rsplit('/').next().unwrap_or(relative);
+            self.pattern.matches(name)
+        } else {
+            let options = glob::MatchOptions {
+                require_literal_separator: true,
+                ..Default::default()
+            };
+            self.pattern.matches_with(relative, options)
+        }
+    }
+}
+
+// Helper function to call `f` with the path and entry of every blob in a tree, recursively.
+// Submodules (commit entries) are skipped since their content isn't in this repository.
+fn for_each_tree_blob<F>(tree: &git2::Tree, mut f: F) -> Result<(), String>
+where
+    F: FnMut(&str, &git2::TreeEntry) -> Result<(), String>,
+{
+    let mut result = Ok(());
+
+    tree.walk(git2::TreeWalkMode::PreOrder, |root, entry| {
+        if entry.kind() != Some(ObjectType::Blob) {
+            return git2::TreeWalkResult::Ok;
+        }
+
+        let path = format!("{}{}", root, entry.name().unwrap_or(""));
+        match f(&path, entry) {
+            Ok(()) => git2::TreeWalkResult::Ok,
+            Err(e) => {
+                result = Err(e);
+                git2::TreeWalkResult::Abort
+            }
+        }
+    })
+    .or_else(|e| {
+        // Aborting the walk is reported as an error by libgit2, keep the real one
+        if result.is_err() {
+            Ok(())
+        } else {
+            Err(e.to_string())
+        }
+    })?;
+
+    result
+}
+
+// Helper function to write blob content to disk honoring the git file mode
+fn write_blob_file(target: &std::path::Path, content: &[u8], filemode: i32) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        if filemode == i32::from(git2::FileMode::Link) {
+            let link_target = String::from_utf8_lossy(content).to_string();
+            return std::os::unix::fs::symlink(link_target, target);
+        }
+
+        fs::write(target, content)?;
+        if filemode == i32::from(git2::FileMode::BlobExecutable) {
+            fs::set_permissions(target, fs::Permissions::from_mode(0o755))?;
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    {
+        // Symlinks are written as plain files containing the link target
+        let _ = filemode;
+        fs::write(target, content)
+    }
+}
+
+// Loose object count above which `git gc --auto` packs (gc.auto default)
+const GC_AUTO_LOOSE_OBJECTS: usize = 6700;
+// Estimated loose object count above which we stop counting exactly
+const MAX_EXACT_LOOSE_OBJECTS: usize = 100_000;
+
+#[tauri::command]
+pub fn get_odb_stats(repo_path: String) -> Result<OdbStats, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    // Linked worktrees share the object database of the main repository
+    let objects_dir = repo.commondir().join("objects");
+
+    // Loose objects are spread evenly over the 256 fan-out directories, so like git gc
+    // one directory is enough to estimate the total. Only count them all if that's cheap
+    let count_dir = |name: &str| -> usize {
+        fs::read_dir(objects_dir.join(name))
+            .map(|entries| entries.flatten().filter(|e| e.path().is_file()).count())
+            .unwrap_or(0)
+    };
+    let estimate = count_dir("17") * 256;
+    let loose_estimated = estimate > MAX_EXACT_LOOSE_OBJECTS;
+    let loose_objects = if loose_estimated {
+        estimate
+    } else {
+        (0..=255u8).map(|i| count_dir(&format!("{:02x}", i))).sum()
+    };
+
+    // The object count of a pack is the last fan-out entry of its index, so packs don't
+    // need to be read
+    let mut packfiles = 0;
+    let mut packed_objects = 0;
+    if let Ok(entries) = fs::read_dir(objects_dir.join("pack")) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("idx") {
+                continue;
+            }
+            if !path.with_extension("pack").exists() {
+                continue;
+            }
+            packfiles += 1;
+            packed_objects += pack_index_object_count(&path).unwrap_or(0);
+        }
+    }
+
+    Ok(OdbStats {
+        loose_objects,
+        loose_estimated,
+        packfiles,
+        packed_objects,
+        total_objects: loose_objects + packed_objects,
+        gc_recommended: loose_objects > GC_AUTO_LOOSE_OBJECTS,
+    })
+}
+
+// Helper function to read the number of objects in a pack from its .idx file
+fn pack_index_object_count(path: &std::path::Path) -> std::io::Result<usize> {
+    use std::io::Read;
+
+    let mut header = [0u8; 8 + 256 * 4];
+    fs::File::open(path)?.read_exact(&mut header)?;
+
+    // Version 2+ indexes start with a magic number and version, version 1 with the fan-out
+    let fanout_start = if header[..4] == [0xff, b't', b'O', b'c'] {
+        8
+    } else {
+        0
+    };
+    let last = fanout_start + 255 * 4;
+    let count = u32::from_be_bytes([
+        header[last],
+        header[last + 1],
+        header[last + 2],
+        header[last + 3],
+    ]);
+
+    Ok(count as usize)
+}
+
+#[tauri::command]
+pub fn get_submodule_status(repo_path: String) -> Result<Vec<SubmoduleState>, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let submodules = repo.submodules().map_err(|e| e.to_string())?;
+
+    let mut result = Vec::new();
+    for submodule in &submodules {
+        let name = submodule.name().unwrap_or("").to_string();
+        let status = repo
+            .submodule_status(&name, git2::SubmoduleIgnore::None)
+            .map_err(|e| format!("Failed to get status of submodule '{}': {}", name, e))?;
+
+        // Ordered by which action the user needs to take first
+        let state = if !status.contains(git2::SubmoduleStatus::IN_CONFIG)
+            || status.contains(git2::SubmoduleStatus::WD_UNINITIALIZED)
+        {
+            // Needs `git submodule update --init`
+            "uninitialized"
+        } else if status.contains(git2::SubmoduleStatus::WD_MODIFIED) {
+            // Checked out commit differs from the recorded one, needs `git submodule update`
+            "out_of_date"
+        } else if status.intersects(
+            git2::SubmoduleStatus::WD_INDEX_MODIFIED
+                | git2::SubmoduleStatus::WD_WD_MODIFIED
+                | git2::SubmoduleStatus::WD_UNTRACKED,
+        ) {
+            // Has uncommitted changes inside the submodule
+            "modified"
+        } else {
+            "up_to_date"
+        };
+
+        result.push(SubmoduleState {
+            name,
+            path: submodule.path().to_string_lossy().to_string(),
+            state: state.to_string(),
+            recorded_oid: submodule.head_id().map(|oid| oid.to_string()),
+            workdir_oid: submodule.workdir_id().map(|oid| oid.to_string()),
+        });
+    }
+
+    Ok(result)
+}
+
+// Commits a submodule bump pulls in, read from the submodule's own checkout, newest first
+#[tauri::command]
+pub fn get_submodule_commit_log(
+    repo_path: String,
+    submodule_name: String,
+    old: String,
+    new: String,
+) -> Result<Vec<GitCommit>, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let submodule = repo.find_submodule(&submodule_name).map_err(|e| {
+        if e.code() == git2::ErrorCode::NotFound {
+            format!("NotFound: Submodule '{}' does not exist", submodule_name)
+        } else {
+            e.to_string()
+        }
+    })?;
+    let sub_repo = submodule.open().map_err(|_| {
+        format!(
+            "NotCheckedOut: Submodule '{}' is not checked out, update it to see its commits",
+            submodule_name
+        )
+    })?;
+
+    let old_oid = Oid::from_str(&old).map_err(|e| format!("Invalid commit ID '{}': {}", old, e))?;
+    let new_oid = Oid::from_str(&new).map_err(|e| format!("Invalid commit ID '{}': {}", new, e))?;
+    // The checkout can be behind the bump, then the commits have to be fetched first
+    for oid in [old_oid, new_oid] {
+        if sub_repo.find_commit(oid).is_err() {
+            return Err(format!(
+                "MissingCommit: {} is not in submodule '{}', update it to fetch the commits",
+                short_id(oid),
+                submodule_name
+            ));
+        }
+    }
+
+    // Equivalent of `git log old..new` inside the submodule
+    let mut walk = sub_repo.revwalk().map_err(|e| e.to_string())?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
+        .map_err(|e| e.to_string())?;
+    walk.push(new_oid).map_err(|e| e.to_string())?;
+    walk.hide(old_oid).map_err(|e| e.to_string())?;
+
+    let mut commits = Vec::new();
+    for oid in walk {
+        let oid = oid.map_err(|e| e.to_string())?;
+        let commit = sub_repo.find_commit(oid).map_err(|e| e.to_string())?;
+        commits.push(undecorated_commit(&commit));
+    }
+
+    Ok(commits)
+}
+
+#[tauri::command]
+pub fn update_submodule(
+    app: tauri::AppHandle,
+    repo_path: String,
+    name: String,
+    init: bool,
+) -> Result<(), String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let mut submodule = repo.find_submodule(&name).map_err(|e| {
+        if e.code() == git2::ErrorCode::NotFound {
+            format!("NotFound: Submodule '{}' does not exist", name)
+        } else {
+            e.to_string()
+        }
+    })?;
+
+    if init {
+        // Copies the submodule's URL from .gitmodules into .git/config
+        submodule
+            .init(false)
+            .map_err(|e| format!("Failed to initialize submodule '{}': {}", name, e))?;
+    }
+
+    let mut callbacks = remote_callbacks(&repo)?;
+    // Progress is reported as events since the command only returns once the fetch is done
+    let progress_name = name.clone();
+    callbacks.transfer_progress(move |progress| {
+        let _ = app.emit(
+            "submodule-update-progress",
+            FetchProgress {
+                name: progress_name.clone(),
+                received_objects: progress.received_objects(),
+                total_objects: progress.total_objects(),
+                received_bytes: progress.received_bytes(),
+            },
+        );
+        true
+    });
+
+    let mut fetch_options = git2::FetchOptions::new();
+    fetch_options.remote_callbacks(callbacks);
+    let mut update_options = git2::SubmoduleUpdateOptions::new();
+    update_options.fetch(fetch_options);
+
+    // Clones the submodule if needed, fetches if the recorded commit is missing, then checks it out
+    submodule
+        .update(init, Some(&mut update_options))
+        .map_err(|e| format!("Failed to update submodule '{}': {}", name, e))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn checkout_commit(repo_path: String, commit_id: String) -> Result<(), String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    // Handle special case for working-copy (shouldn't happen, but just in case)
+    if commit_id == WORKING_COPY_ID {
+        return Err("Cannot checkout working-copy".to_string());
+    }
+
+    // Parse the commit ID
+    let oid = Oid::from_str(&commit_id)
+        .map_err(|e| format!("Invalid commit ID '{}': {}", commit_id, e))?;
+
+    // Find the commit object to verify it exists
+    let _commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+
+    // Create an object from the commit
+    let obj = repo.find_object(oid, None).map_err(|e| e.to_string())?;
+
+    // Checkout the commit without force
+    // This will behave like standard git checkout
+    let mut checkout_builder = git2::build::CheckoutBuilder::new();
+    // Remove force() to allow standard git behavior
+    // This will fail if there are uncommitted changes that would be overwritten
+
+    match repo.checkout_tree(&obj, Some(&mut checkout_builder)) {
+        Ok(()) => {
+            // Update HEAD to point to this commit (detached HEAD state)
+            repo.set_head_detached(oid).map_err(|e| e.to_string())?;
+            Ok(())
+        }
+        Err(e) => {
+            // Provide more helpful error message
+            if e.message().contains("conflict") || e.message().contains("dirty") {
+                Err("Cannot checkout: You have uncommitted changes that would be overwritten. Please commit or stash your changes first.".to_string())
+            } else {
+                Err(format!("Failed to checkout commit: {}", e))
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub fn checkout_branch(repo_path: String, branch_name: String) -> Result<(), String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    // Determine the correct reference name and HEAD target
+    let (ref_name, head_target) = {
+        // First try local branch (refs/heads/...)
+        if let Ok(_branch_ref) = repo.find_reference(&format!("refs/heads/{}", branch_name)) {
+            (
+                format!("refs/heads/{}", branch_name),
+                format!("refs/heads/{}", branch_name),
+            )
+        }
+        // Then try remote branch (refs/remotes/...)
+        else if let Ok(remote_ref) = repo.find_reference(&format!("refs/remotes/{}", branch_name))
+        {
+            // For remote branches, we need to create a local tracking branch or checkout in detached HEAD
+            // For simplicity, let's checkout the remote branch directly (detached HEAD)
+            // But first check if there's already a local branch with the same name (without remote prefix)
+            let local_branch_name = branch_name.split('/').last().unwrap_or(&branch_name);
+            if let Ok(_) = repo.find_reference(&format!("refs/heads/{}", local_branch_name)) {
+                // Local branch exists, use it
+                (
+                    format!("refs/heads/{}", local_branch_name),
+                    format!("refs/heads/{}", local_branch_name),
+                )
+            } else {
+                // No local branch, checkout the remote commit directly (detached HEAD)
+                let target_oid = remote_ref
+                    .target()
+                    .ok_or_else(|| "Remote branch has no target".to_string())?;
+                return checkout_commit_by_oid(&repo, target_oid, &repo_path);
+            }
+        }
+        // Try full reference name
+        else if let Ok(full_ref) = repo.find_reference(&branch_name) {
+            if branch_name.starts_with("refs/heads/") {
+                (branch_name.clone(), branch_name.clone())
+            } else {
+                // Non-local reference, checkout commit directly
+                let target_oid = full_ref
+                    .target()
+                    .ok_or_else(|| "Reference has no target".to_string())?;
+                return checkout_commit_by_oid(&repo, target_oid, &repo_path);
+            }
+        }
+        // Branch not found
+        else {
+            return Err(format!("Branch '{}' not found", branch_name));
+        }
+    };
+
+    // Get the target commit OID
+    let branch_ref = repo.find_reference(&ref_name).map_err(|e| e.to_string())?;
+    let target_oid = branch_ref
+        .target()
+        .ok_or_else(|| "Branch has no target commit".to_string())?;
+
+    // Find the commit object to verify it exists
+    let _commit = repo.find_commit(target_oid).map_err(|e| e.to_string())?;
+
+    // Create an object from the commit
+    let obj = repo
+        .find_object(target_oid, None)
+        .map_err(|e| e.to_string())?;
+
+    // Checkout the branch
+    let mut checkout_builder = git2::build::CheckoutBuilder::new();
+
+    match repo.checkout_tree(&obj, Some(&mut checkout_builder)) {
+        Ok(()) => {
+            // Update HEAD to point to the branch
+            repo.set_head(&head_target).map_err(|e| e.to_string())?;
+            Ok(())
+        }
+        Err(e) => {
+            // Provide more helpful error message
+            if e.message().contains("conflict") || e.message().contains("dirty") {
+                Err("Cannot checkout: You have uncommitted changes that would be overwritten. Please commit or stash your changes first.".to_string())
+            } else {
+                Err(format!(
+                    "Failed to checkout branch '{}': {}",
+                    branch_name, e
+                ))
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub fn switch_branch_autostash(repo_path: String, branch: String) -> Result<SwitchResult, String> {
+    let mut repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    // Untracked files survive a checkout, so only tracked changes need stashing
+    let stashed = ensure_clean_worktree(&repo).is_err();
+    if stashed {
+        let signature = default_signature(&repo)?;
+        let message = format!("autostash before switching to {}", branch);
+        repo.stash_save(&signature, &message, None)
+            .map_err(|e| format!("Failed to stash changes: {}", e))?;
+    }
+
+    let checkout_result = checkout_branch(repo_path.clone(), branch.clone());
+    // checkout_branch works on its own handle, so the index cached by ours is stale
+    let mut repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    if let Err(e) = checkout_result {
+        // Put the changes back where they were before reporting the failure
+        if stashed {
+            let mut options = StashApplyOptions::new();
+            if repo.stash_pop(0, Some(&mut options)).is_err() {
+                return Err(format!("{} Your changes are kept in stash@{{0}}.", e));
+            }
+        }
+        return Err(e);
+    }
+
+    if !stashed {
+        return Ok(SwitchResult {
+            stashed: false,
+            restored: false,
+            conflicted: false,
+            stash_ref: None,
+        });
+    }
+
+    // Like `git rebase --autostash`: on conflict the stash is kept so nothing is lost.
+    // libgit2 reports conflicts by leaving them in the index rather than failing
+    let mut options = StashApplyOptions::new();
+    repo.stash_apply(0, Some(&mut options)).map_err(|e| {
+        format!(
+            "Switched to '{}' but failed to restore your changes, they are kept in stash@{{0}}: {}",
+            branch, e
+        )
+    })?;
+
+    let conflicted = repo.index().map_err(|e| e.to_string())?.has_conflicts();
+    if conflicted {
+        return Ok(SwitchResult {
+            stashed: true,
+            restored: false,
+            conflicted: true,
+            stash_ref: Some("stash@{0}".to_string()),
+        });
+    }
+
+    repo.stash_drop(0)
+        .map_err(|e| format!("Failed to drop stash after restoring it: {}", e))?;
+
+    Ok(SwitchResult {
+        stashed: true,
+        restored: true,
+        conflicted: false,
+        stash_ref: None,
+    })
+}
+
+// Helper function to checkout a commit by OID (detached HEAD)
+fn checkout_commit_by_oid(repo: &Repository, oid: Oid, _repo_path: &str) -> Result<(), String> {
+    let obj = repo.find_object(oid, None).map_err(|e| e.to_string())?;
+    let mut checkout_builder = git2::build::CheckoutBuilder::new();
+
+    match repo.checkout_tree(&obj, Some(&mut checkout_builder)) {
+        Ok(()) => {
+            // Update HEAD to point to this commit (detached HEAD state)
+            repo.set_head_detached(oid).map_err(|e| e.to_string())?;
+            Ok(())
+        }
+        Err(e) => {
+            if e.message().contains("conflict") || e.message().contains("dirty") {
+                Err("Cannot checkout: You have uncommitted changes that would be overwritten. Please commit or stash your changes first.".to_string())
+            } else {
+                Err(format!("Failed to checkout commit: {}", e))
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_head_reflog(repo_path: String, limit: usize) -> Result<Vec<ReflogEntry>, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let reflog = repo
+        .reflog("HEAD")
+        .map_err(|e| format!("Failed to read HEAD reflog: {}", e))?;
+
+    // Newest first, the way `git reflog` lists them
+    let entries = reflog
+        .iter()
+        .take(limit)
+        .map(|entry| {
+            let message = entry.message().unwrap_or("").to_string();
+            ReflogEntry {
+                old_oid: entry.id_old().to_string(),
+                new_oid: entry.id_new().to_string(),
+                operation: reflog_operation(&message),
+                message,
+                committer: entry.committer().name().unwrap_or("").to_string(),
+                date: entry.committer().when().seconds(),
+            }
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+// Helper function to categorize a reflog message by what wrote it. Git and this app both
+// prefix messages with the operation, e.g. "commit (amend): ..." or "fast-forward: ..."
+fn reflog_operation(message: &str) -> String {
+    let prefix = match message.split_once(':') {
+        Some((prefix, _)) => prefix.trim(),
+        None => return "other".to_string(),
+    };
+
+    // "commit (amend)" and "commit (merge)" are more useful than plain "commit"
+    if prefix == "commit (amend)" {
+        return "amend".to_string();
+    }
+    if prefix == "commit (merge)" {
+        return "merge".to_string();
+    }
+
+    // Drop qualifiers like "rebase (finish)", "pull --rebase" or "commit (initial)"
+    let operation = prefix
+        .split(|c: char| c == '(' || c.is_whitespace())
+        .next()
+        .unwrap_or("")
+        .to_lowercase();
+    if operation.is_empty() {
+        "other".to_string()
+    } else {
+        operation
+    }
+}
+
+#[tauri::command]
+pub fn undo_commits(repo_path: String, n: usize, keep_changes: bool) -> Result<String, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    if n == 0 {
+        return Err("Number of commits to undo must be at least 1".to_string());
+    }
+
+    if repo.head_detached().map_err(|e| e.to_string())? {
+        return Err(
+            "Cannot undo commits in detached HEAD state. Please checkout a branch first."
+                .to_string(),
+        );
+    }
+
+    let head_commit = repo
+        .head()
+        .and_then(|h| h.peel_to_commit())
+        .map_err(|e| format!("Failed to resolve HEAD: {}", e))?;
+
+    // Follow first parents, like HEAD~n
+    let mut target = head_commit;
+    for i in 0..n {
+        target = target.parent(0).map_err(|_| {
+            format!(
+                "Cannot undo {} commits: the branch only has {} commits before HEAD",
+                n, i
+            )
+        })?;
+    }
+
+    // Mixed keeps the undone changes in the working tree, hard discards them
+    let reset_type = if keep_changes {
+        git2::ResetType::Mixed
+    } else {
+        git2::ResetType::Hard
+    };
+    repo.reset(target.as_object(), reset_type, None)
+        .map_err(|e| format!("Failed to reset: {}", e))?;
+
+    Ok(target.id().to_string())
+}
+
+#[tauri::command]
+pub fn reorder_commits(repo_path: String, new_order: Vec<String>) -> Result<Vec<String>, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let (branch_ref, head_commit) = current_branch_head(&repo)?;
+    ensure_clean_worktree(&repo)?;
+
+    // new_order lists the commits oldest first, i.e. in the order they will be applied
+    let new_order = parse_commit_ids(&new_order)?;
+    if new_order.is_empty() {
+        return Err("No commits to reorder".to_string());
+    }
+
+    // The range to rewrite is everything from the deepest listed commit up to HEAD
+    let chain = first_parent_chain(&head_commit, &new_order)?;
+    let mut expected = chain.clone();
+    let mut given = new_order.clone();
+    expected.sort();
+    given.sort();
+    if expected != given {
+        return Err(format!(
+            "The new order must contain exactly the {} commits from {} up to HEAD",
+            chain.len(),
+            short_id(chain[0])
+        ));
+    }
+
+    let base = repo
+        .find_commit(chain[0])
+        .and_then(|c| c.parent(0))
+        .map_err(|_| "Cannot reorder the root commit".to_string())?;
+
+    let steps: Vec<ReplayStep> = new_order.into_iter().map(ReplayStep::pick).collect();
+    let new_oids = replay_commits(&repo, base, &steps, &default_signature(&repo)?)?;
+
+    let new_head = *new_oids.last().unwrap();
+    finish_rewrite(&repo, &branch_ref, new_head, "reorder commits")?;
+
+    Ok(new_oids.iter().map(|oid| oid.to_string()).collect())
+}
+
+#[tauri::command]
+pub fn squash_commits(
+    repo_path: String,
+    commit_ids: Vec<String>,
+    message: String,
+) -> Result<String, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let (branch_ref, head_commit) = current_branch_head(&repo)?;
+    ensure_clean_worktree(&repo)?;
+
+    if message.trim().is_empty() {
+        return Err("Commit message cannot be empty".to_string());
+    }
+
+    let mut commit_ids = parse_commit_ids(&commit_ids)?;
+    let mut seen = std::collections::HashSet::new();
+    commit_ids.retain(|oid| seen.insert(*oid));
+    if commit_ids.len() < 2 {
+        return Err("Select at least two commits to squash".to_string());
+    }
+
+    // The squashed commits must be the oldest part of the chain, with no gaps
+    let chain = first_parent_chain(&head_commit, &commit_ids)?;
+    if chain.len() < commit_ids.len() {
+        return Err("The selected commits must be contiguous".to_string());
+    }
+    let (range, later) = chain.split_at(commit_ids.len());
+    if !commit_ids.iter().all(|oid| range.contains(oid)) {
+        return Err("The selected commits must be contiguous".to_string());
+    }
+
+    let base = repo
+        .find_commit(range[0])
+        .and_then(|c| c.parent(0))
+        .map_err(|_| "Cannot squash the root commit".to_string())?;
+
+    let mut steps: Vec<ReplayStep> = range
+        .iter()
+        .enumerate()
+        .map(|(i, oid)| ReplayStep {
+            oid: *oid,
+            message: Some(message.clone()),
+            author: None,
+            committer: None,
+            squash: i > 0,
+        })
+        .collect();
+    steps.extend(later.iter().copied().map(ReplayStep::pick));
+
+    let new_oids = replay_commits(&repo, base, &steps, &default_signature(&repo)?)?;
+
+    let new_head = *new_oids.last().unwrap();
+    finish_rewrite(&repo, &branch_ref, new_head, "squash commits")?;
+
+    Ok(new_head.to_string())
+}
+
+#[tauri::command]
+pub fn drop_commit(repo_path: String, commit_id: String) -> Result<String, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let (branch_ref, head_commit) = current_branch_head(&repo)?;
+    ensure_clean_worktree(&repo)?;
+
+    let oid = Oid::from_str(&commit_id)
+        .map_err(|e| format!("Invalid commit ID '{}': {}", commit_id, e))?;
+
+    let chain = first_parent_chain(&head_commit, &[oid])?;
+    let base = repo
+        .find_commit(oid)
+        .and_then(|c| c.parent(0))
+        .map_err(|_| "Cannot drop the root commit".to_string())?;
+    let base_oid = base.id();
+
+    // Replay everything after the dropped commit onto its parent
+    let steps: Vec<ReplayStep> = chain[1..].iter().copied().map(ReplayStep::pick).collect();
+    let new_oids = replay_commits(&repo, base, &steps, &default_signature(&repo)?)?;
+
+    let new_head = new_oids.last().copied().unwrap_or(base_oid);
+    finish_rewrite(&repo, &branch_ref, new_head, "drop commit")?;
+
+    Ok(new_head.to_string())
+}
+
+#[tauri::command]
+pub fn reword_commit(
+    repo_path: String,
+    commit_id: String,
+    new_message: String,
+    author: Option<(String, String)>,
+    committer: Option<(String, String)>,
+) -> Result<String, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let (branch_ref, head_commit) = current_branch_head(&repo)?;
+
+    if new_message.trim().is_empty() {
+        return Err("Commit message cannot be empty".to_string());
+    }
+
+    // Without an explicit author the original one is kept, like `git commit --amend`
+    let author = author
+        .map(|(name, email)| explicit_signature(&name, &email))
+        .transpose()?;
+    // Only the reworded commit gets the explicit committer, later ones are replayed as usual
+    let committer = committer
+        .map(|(name, email)| explicit_signature(&name, &email))
+        .transpose()?;
+    let default_committer = default_signature(&repo)?;
+
+    let oid = Oid::from_str(&commit_id)
+        .map_err(|e| format!("Invalid commit ID '{}': {}", commit_id, e))?;
+
+    // Fast path: amending HEAD doesn't require replaying anything
+    if oid == head_commit.id() {
+        let new_head = head_commit
+            .amend(
+                Some("HEAD"),
+                author.as_ref(),
+                Some(committer.as_ref().unwrap_or(&default_committer)),
+                None,
+                Some(&new_message),
+                None,
+            )
+            .map_err(|e| format!("Failed to amend commit: {}", e))?;
+        return Ok(new_head.to_string());
+    }
+
+    // Trees are unchanged by a reword, so uncommitted changes are not at risk here
+    let chain = first_parent_chain(&head_commit, &[oid])?;
+    let base = repo
+        .find_commit(oid)
+        .and_then(|c| c.parent(0))
+        .map_err(|_| "Cannot reword the root commit".to_string())?;
+
+    let mut steps = vec![ReplayStep {
+        oid,
+        message: Some(new_message),
+        author,
+        committer,
+        squash: false,
+    }];
+    steps.extend(chain[1..].iter().copied().map(ReplayStep::pick));
+
+    let new_oids = replay_commits(&repo, base, &steps, &default_committer)?;
+
+    let new_head = *new_oids.last().unwrap();
+    finish_rewrite(&repo, &branch_ref, new_head, "reword commit")?;
+
+    Ok(new_head.to_string())
+}
+
+#[tauri::command]
+pub fn cherry_pick_range(
+    repo_path: String,
+    commit_ids: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let (branch_ref, head_commit) = current_branch_head(&repo)?;
+    ensure_clean_worktree(&repo)?;
+
+    let oids = parse_commit_ids(&commit_ids)?;
+    if oids.is_empty() {
+        return Err("No commits to cherry-pick".to_string());
+    }
+    let committer = default_signature(&repo)?;
+
+    let mut tip = head_commit;
+    let mut new_oids: Vec<Oid> = Vec::new();
+    let mut stopped_at: Option<String> = None;
+
+    for oid in oids {
+        let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+        if commit.parent_count() > 1 {
+            stopped_at = Some(format!("Cannot cherry-pick merge commit {}", short_id(oid)));
+            break;
+        }
+
+        // Applied in memory, so a conflict never leaves a half-applied index or working tree
+        let mut index = repo
+            .cherrypick_commit(&commit, &tip, 0, None)
+            .map_err(|e| format!("Failed to apply {}: {}", short_id(oid), e))?;
+        if index.has_conflicts() {
+            stopped_at = Some(format!(
+                "Conflict while cherry-picking {} ({}): {}",
+                short_id(oid),
+                commit.summary().unwrap_or(""),
+                conflicted_paths(&index)?.join(", ")
+            ));
+            break;
+        }
+
+        let tree_oid = index.write_tree_to(&repo).map_err(|e| e.to_string())?;
+        // The change is already on the branch, nothing to commit
+        if tree_oid == tip.tree_id() {
+            continue;
+        }
+        let tree = repo.find_tree(tree_oid).map_err(|e| e.to_string())?;
+
+        let new_oid = repo
+            .commit(
+                None,
+                &commit.author(),
+                &committer,
+                commit.message().unwrap_or(""),
+                &tree,
+                &[&tip],
+            )
+            .map_err(|e| format!("Failed to create commit: {}", e))?;
+        new_oids.push(new_oid);
+        tip = repo.find_commit(new_oid).map_err(|e| e.to_string())?;
+    }
+
+    // Commits applied before a conflict are kept, like `git cherry-pick` does
+    if let Some(&new_head) = new_oids.last() {
+        finish_rewrite(&repo, &branch_ref, new_head, "cherry-pick")?;
+    }
+
+    match stopped_at {
+        Some(reason) if new_oids.is_empty() => Err(format!("{}. No commits were applied.", reason)),
+        Some(reason) => Err(format!(
+            "{}. Stopped after applying {} commit(s): {}",
+            reason,
+            new_oids.len(),
+            new_oids
+                .iter()
+                .map(|oid| oid.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+        None => Ok(new_oids.iter().map(|oid| oid.to_string()).collect()),
+    }
+}
+
+// Checks the conditions the history editing commands depend on, so the UI can warn first
+#[tauri::command]
+pub fn can_modify_history(repo_path: String) -> Result<HistoryEditability, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let clean_worktree = ensure_clean_worktree(&repo).is_ok();
+
+    let head = match repo.head() {
+        Ok(head) if head.is_branch() => head,
+        _ => {
+            return Ok(HistoryEditability {
+                on_branch: false,
+                branch: None,
+                has_upstream: false,
+                behind_upstream: false,
+                head_pushed: false,
+                clean_worktree,
+            })
+        }
+    };
+    let branch_name = head.shorthand().unwrap_or("").to_string();
+    let head_oid = head
+        .target()
+        .ok_or_else(|| "Branch has no target commit".to_string())?;
+
+    let upstream_oid = repo
+        .find_branch(&branch_name, BranchType::Local)
+        .and_then(|branch| branch.upstream())
+        .ok()
+        .and_then(|upstream| upstream.get().target());
+    let (behind_upstream, head_pushed) = match upstream_oid {
+        Some(upstream_oid) => {
+            let (_, behind) = repo
+                .graph_ahead_behind(head_oid, upstream_oid)
+                .map_err(|e| e.to_string())?;
+            let pushed = head_oid == upstream_oid
+                || repo
+                    .graph_descendant_of(upstream_oid, head_oid)
+                    .map_err(|e| e.to_string())?;
+            (behind > 0, pushed)
+        }
+        None => (false, false),
+    };
+
+    Ok(HistoryEditability {
+        on_branch: true,
+        branch: Some(branch_name),
+        has_upstream: upstream_oid.is_some(),
+        behind_upstream,
+        head_pushed,
+        clean_worktree,
+    })
+}
 
-    let old_tree = old_commit_obj.tree().map_err(|e| e.to_string())?;
-    let new_tree = new_commit_obj.tree().map_err(|e| e.to_string())?;
+// One commit to re-apply during a history rewrite
+struct ReplayStep {
+    oid: Oid,
+    message: Option<String>, // replaces the original message when set
+    author: Option<git2::Signature<'static>>, // replaces the original author when set
+    committer: Option<git2::Signature<'static>>, // replaces the replay committer when set
+    squash: bool, // fold into the previously replayed commit instead of creating a new one
+}
 
-    let diff = repo
-        .diff_tree_to_tree(Some(&old_tree), Some(&new_tree), None)
-        .map_err(|e| e.to_string())?;
+impl ReplayStep {
+    fn pick(oid: Oid) -> Self {
+        ReplayStep {
+            oid,
+            message: None,
+            author: None,
+            committer: None,
+            squash: false,
+        }
+    }
+}
 
-    println!("Diff object created, getting deltas...");
+// Helper function to replay commits on top of `onto`, entirely in memory.
+// libgit2's Rebase API can only replay commits in their original order, so history editing
+// cherry-picks each step into an in-memory index instead. Nothing is written to refs or the
+// working tree here, so a conflict leaves the repository untouched.
+// Returns the OIDs of the newly created commits, oldest first.
+fn replay_commits(
+    repo: &Repository,
+    onto: git2::Commit,
+    steps: &[ReplayStep],
+    committer: &git2::Signature,
+) -> Result<Vec<Oid>, String> {
+    let mut tip = onto;
+    let mut new_oids: Vec<Oid> = Vec::new();
 
-    let mut files = Vec::new();
+    for step in steps {
+        let commit = repo.find_commit(step.oid).map_err(|e| e.to_string())?;
+        if commit.parent_count() > 1 {
+            return Err(format!(
+                "Cannot rewrite history containing merge commit {}",
+                short_id(step.oid)
+            ));
+        }
 
-    for delta in diff.deltas() {
-        println!("Processing delta");
+        let mut index = repo
+            .cherrypick_commit(&commit, &tip, 0, None)
+            .map_err(|e| format!("Failed to apply {}: {}", short_id(step.oid), e))?;
 
-        // Get the file path from either new_file or old_file
-        let file_path = delta
-            .new_file()
-            .path()
-            .or_else(|| delta.old_file().path())
-            .map(|p| {
-                let path_str = p.to_string_lossy().to_string();
-                println!("Processing file: {}", path_str);
-                path_str
-            })
-            .unwrap_or_else(|| {
-                println!("Unknown file path in delta");
-                String::from("unknown")
-            });
+        if index.has_conflicts() {
+            return Err(format!(
+                "Conflict while applying {} ({}): {}. No changes were made.",
+                short_id(step.oid),
+                commit.summary().unwrap_or(""),
+                conflicted_paths(&index)?.join(", ")
+            ));
+        }
 
-        // Get old content
-        let old_content = if let Some(old_path) = delta.old_file().path() {
-            println!("Getting old content from: {:?}", old_path);
-            get_file_content(&repo, &old_tree, &file_path, Some(old_path))
-        } else {
-            println!("File was added (no old content)");
-            String::new() // File was added
-        };
+        let tree_oid = index.write_tree_to(repo).map_err(|e| e.to_string())?;
+        let tree = repo.find_tree(tree_oid).map_err(|e| e.to_string())?;
 
-        // Get new content
-        let new_content = if let Some(new_path) = delta.new_file().path() {
-            println!("Getting new content from: {:?}", new_path);
-            get_file_content(&repo, &new_tree, &file_path, Some(new_path))
+        let new_oid = if step.squash && !new_oids.is_empty() {
+            // Replace the previous commit, keeping its author and parents
+            let parents: Vec<git2::Commit> = tip.parents().collect();
+            let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+            let message = step
+                .message
+                .clone()
+                .unwrap_or_else(|| tip.message().unwrap_or("").to_string());
+            new_oids.pop();
+            let author = step
+                .author
+                .clone()
+                .unwrap_or_else(|| tip.author().to_owned());
+            let committer = step.committer.as_ref().unwrap_or(committer);
+            repo.commit(None, &author, committer, &message, &tree, &parent_refs)
+                .map_err(|e| format!("Failed to create commit: {}", e))?
         } else {
-            println!("File was deleted (no new content)");
-            String::new() // File was deleted
+            let message = step
+                .message
+                .clone()
+                .unwrap_or_else(|| commit.message().unwrap_or("").to_string());
+            let author = step
+                .author
+                .clone()
+                .unwrap_or_else(|| commit.author().to_owned());
+            let committer = step.committer.as_ref().unwrap_or(committer);
+            repo.commit(None, &author, committer, &message, &tree, &[&tip])
+                .map_err(|e| format!("Failed to create commit: {}", e))?
         };
 
-        files.push(FileDiff {
-            path: file_path,
-            old_content,
-            new_content,
-        });
+        new_oids.push(new_oid);
+        tip = repo.find_commit(new_oid).map_err(|e| e.to_string())?;
     }
 
-    println!("Returning {} files", files.len());
-    Ok(DiffResponse { files })
+    Ok(new_oids)
 }
 
-fn get_file_content(
+// Helper function to point the branch at the rewritten history and update the working tree
+fn finish_rewrite(
     repo: &Repository,
-    tree: &git2::Tree,
-    file_path: &str,
-    entry_path: Option<&std::path::Path>,
-) -> String {
-    let path_to_find = entry_path.unwrap_or_else(|| std::path::Path::new(file_path));
+    branch_ref: &str,
+    new_head: Oid,
+    operation: &str,
+) -> Result<(), String> {
+    let obj = repo
+        .find_object(new_head, None)
+        .map_err(|e| e.to_string())?;
+    let mut checkout_builder = git2::build::CheckoutBuilder::new();
+    repo.checkout_tree(&obj, Some(&mut checkout_builder))
+        .map_err(|e| format!("Failed to checkout rewritten history: {}", e))?;
 
-    // Try to find the file in the tree
-    let tree_entry = match tree.get_path(path_to_find) {
-        Ok(entry) => entry,
-        Err(e) => {
-            eprintln!("Failed to get path {:?} from tree: {}", path_to_find, e);
-            return String::new();
-        }
-    };
+    let log_message = format!("{}: moving to {}", operation, new_head);
+    repo.reference(branch_ref, new_head, true, &log_message)
+        .map_err(|e| format!("Failed to update branch: {}", e))?;
 
-    if tree_entry.kind() != Some(ObjectType::Blob) {
-        eprintln!(
-            "Entry {:?} is not a blob, it's a {:?}",
-            path_to_find,
-            tree_entry.kind()
+    Ok(())
+}
+
+// Helper function to get the current branch ref name and its commit, refusing detached HEAD
+fn current_branch_head(repo: &Repository) -> Result<(String, git2::Commit<'_>), String> {
+    if repo.head_detached().map_err(|e| e.to_string())? {
+        return Err(
+            "Cannot rewrite history in detached HEAD state. Please checkout a branch first."
+                .to_string(),
         );
-        return String::new();
     }
 
-    let obj = match repo.find_object(tree_entry.id(), Some(ObjectType::Blob)) {
-        Ok(obj) => obj,
-        Err(e) => {
-            eprintln!("Failed to find object {:?}: {}", tree_entry.id(), e);
-            return String::new();
-        }
-    };
+    let head = repo
+        .head()
+        .map_err(|e| format!("Failed to resolve HEAD: {}", e))?;
+    let name = head
+        .name()
+        .ok_or_else(|| "HEAD has an invalid name".to_string())?
+        .to_string();
+    let commit = head.peel_to_commit().map_err(|e| e.to_string())?;
 
-    let blob = obj.as_blob().unwrap();
-    String::from_utf8_lossy(blob.content()).to_string()
+    Ok((name, commit))
 }
 
-#[tauri::command]
-pub fn checkout_commit(repo_path: String, commit_id: String) -> Result<(), String> {
-    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+// Helper function to refuse operations that would overwrite uncommitted changes
+fn ensure_clean_worktree(repo: &Repository) -> Result<(), String> {
+    let mut status_opts = StatusOptions::new();
+    status_opts.include_untracked(false);
+    let statuses = repo
+        .statuses(Some(&mut status_opts))
+        .map_err(|e| e.to_string())?;
 
-    // Handle special case for working-copy (shouldn't happen, but just in case)
-    if commit_id == "working-copy" {
-        return Err("Cannot checkout working-copy".to_string());
+    if !statuses.is_empty() {
+        return Err(
+            "You have uncommitted changes. Please commit or stash your changes first.".to_string(),
+        );
     }
 
-    // Parse the commit ID
-    let oid = Oid::from_str(&commit_id)
-        .map_err(|e| format!("Invalid commit ID '{}': {}", commit_id, e))?;
+    Ok(())
+}
 
-    // Find the commit object to verify it exists
-    let _commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+// Helper function to walk the first-parent line from HEAD until all `targets` were seen.
+// Returns the commits from the deepest target up to HEAD, oldest first.
+fn first_parent_chain(head: &git2::Commit, targets: &[Oid]) -> Result<Vec<Oid>, String> {
+    let mut remaining: std::collections::HashSet<Oid> = targets.iter().copied().collect();
+    let mut chain = Vec::new();
+    let mut current = Some(head.clone());
 
-    // Create an object from the commit
-    let obj = repo.find_object(oid, None).map_err(|e| e.to_string())?;
+    while let Some(commit) = current {
+        remaining.remove(&commit.id());
+        chain.push(commit.id());
+        if remaining.is_empty() {
+            chain.reverse();
+            return Ok(chain);
+        }
+        current = commit.parent(0).ok();
+    }
 
-    // Checkout the commit without force
-    // This will behave like standard git checkout
-    let mut checkout_builder = git2::build::CheckoutBuilder::new();
-    // Remove force() to allow standard git behavior
-    // This will fail if there are uncommitted changes that would be overwritten
+    let missing: Vec<String> = remaining.into_iter().map(short_id).collect();
+    Err(format!(
+        "Commits not on the current branch: {}",
+        missing.join(", ")
+    ))
+}
 
-    match repo.checkout_tree(&obj, Some(&mut checkout_builder)) {
-        Ok(()) => {
-            // Update HEAD to point to this commit (detached HEAD state)
-            repo.set_head_detached(oid).map_err(|e| e.to_string())?;
-            Ok(())
-        }
-        Err(e) => {
-            // Provide more helpful error message
-            if e.message().contains("conflict") || e.message().contains("dirty") {
-                Err("Cannot checkout: You have uncommitted changes that would be overwritten. Please commit or stash your changes first.".to_string())
-            } else {
-                Err(format!("Failed to checkout commit: {}", e))
-            }
+// Helper function to list the paths with conflicts in an in-memory merge result
+fn conflicted_paths(index: &git2::Index) -> Result<Vec<String>, String> {
+    let mut paths = Vec::new();
+    for conflict in index.conflicts().map_err(|e| e.to_string())?.flatten() {
+        if let Some(entry) = conflict.our.or(conflict.their).or(conflict.ancestor) {
+            paths.push(String::from_utf8_lossy(&entry.path).to_string());
         }
     }
+    Ok(paths)
 }
 
-#[tauri::command]
-pub fn checkout_branch(repo_path: String, branch_name: String) -> Result<(), String> {
-    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+// Helper function to parse a list of commit IDs
+fn parse_commit_ids(commit_ids: &[String]) -> Result<Vec<Oid>, String> {
+    commit_ids
+        .iter()
+        .map(|id| Oid::from_str(id).map_err(|e| format!("Invalid commit ID '{}': {}", id, e)))
+        .collect()
+}
 
-    // Determine the correct reference name and HEAD target
-    let (ref_name, head_target) = {
-        // First try local branch (refs/heads/...)
-        if let Ok(_branch_ref) = repo.find_reference(&format!("refs/heads/{}", branch_name)) {
-            (
-                format!("refs/heads/{}", branch_name),
-                format!("refs/heads/{}", branch_name),
-            )
-        }
-        // Then try remote branch (refs/remotes/...)
-        else if let Ok(remote_ref) = repo.find_reference(&format!("refs/remotes/{}", branch_name))
-        {
-            // For remote branches, we need to create a local tracking branch or checkout in detached HEAD
-            // For simplicity, let's checkout the remote branch directly (detached HEAD)
-            // But first check if there's already a local branch with the same name (without remote prefix)
-            let local_branch_name = branch_name.split('/').last().unwrap_or(&branch_name);
-            if let Ok(_) = repo.find_reference(&format!("refs/heads/{}", local_branch_name)) {
-                // Local branch exists, use it
-                (
-                    format!("refs/heads/{}", local_branch_name),
-                    format!("refs/heads/{}", local_branch_name),
-                )
-            } else {
-                // No local branch, checkout the remote commit directly (detached HEAD)
-                let target_oid = remote_ref
-                    .target()
-                    .ok_or_else(|| "Remote branch has no target".to_string())?;
-                return checkout_commit_by_oid(&repo, target_oid, &repo_path);
-            }
-        }
-        // Try full reference name
-        else if let Ok(full_ref) = repo.find_reference(&branch_name) {
-            if branch_name.starts_with("refs/heads/") {
-                (branch_name.clone(), branch_name.clone())
-            } else {
-                // Non-local reference, checkout commit directly
-                let target_oid = full_ref
-                    .target()
-                    .ok_or_else(|| "Reference has no target".to_string())?;
-                return checkout_commit_by_oid(&repo, target_oid, &repo_path);
-            }
+// Helper function to abbreviate an OID for messages
+fn short_id(oid: Oid) -> String {
+    oid.to_string()[..7].to_string()
+}
+
+// Helper function to build remote callbacks that authenticate like the git CLI would:
+// SSH keys through the agent, HTTPS through the configured credential helper
+fn remote_callbacks<'a>(repo: &Repository) -> Result<git2::RemoteCallbacks<'a>, String> {
+    let config = repo.config().map_err(|e| e.to_string())?;
+    let mut attempts = 0;
+
+    let mut callbacks = git2::RemoteCallbacks::new();
+    callbacks.credentials(move |url, username_from_url, allowed_types| {
+        // libgit2 keeps asking while credentials are rejected, so give up after a few tries
+        attempts += 1;
+        if attempts > 3 {
+            return Err(git2::Error::from_str(&format!(
+                "Authentication failed for '{}'",
+                url
+            )));
         }
-        // Branch not found
-        else {
-            return Err(format!("Branch '{}' not found", branch_name));
+
+        if allowed_types.contains(git2::CredentialType::SSH_KEY) {
+            git2::Cred::ssh_key_from_agent(username_from_url.unwrap_or("git"))
+        } else if allowed_types.contains(git2::CredentialType::USER_PASS_PLAINTEXT) {
+            git2::Cred::credential_helper(&config, url, username_from_url)
+        } else {
+            git2::Cred::default()
         }
-    };
+    });
 
-    // Get the target commit OID
-    let branch_ref = repo.find_reference(&ref_name).map_err(|e| e.to_string())?;
-    let target_oid = branch_ref
-        .target()
-        .ok_or_else(|| "Branch has no target commit".to_string())?;
+    Ok(callbacks)
+}
 
-    // Find the commit object to verify it exists
-    let _commit = repo.find_commit(target_oid).map_err(|e| e.to_string())?;
+#[tauri::command]
+pub fn get_remotes(repo_path: String) -> Result<Vec<GitRemote>, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let names = repo.remotes().map_err(|e| e.to_string())?;
 
-    // Create an object from the commit
-    let obj = repo
-        .find_object(target_oid, None)
-        .map_err(|e| e.to_string())?;
+    let mut remotes = Vec::new();
+    for name in names.iter().flatten() {
+        let remote = repo
+            .find_remote(name)
+            .map_err(|e| format!("Remote '{}' not found: {}", name, e))?;
+        remotes.push(GitRemote {
+            name: name.to_string(),
+            url: remote.url().map(|url| url.to_string()),
+            push_url: remote.pushurl().map(|url| url.to_string()),
+        });
+    }
 
-    // Checkout the branch
-    let mut checkout_builder = git2::build::CheckoutBuilder::new();
+    Ok(remotes)
+}
 
-    match repo.checkout_tree(&obj, Some(&mut checkout_builder)) {
-        Ok(()) => {
-            // Update HEAD to point to the branch
-            repo.set_head(&head_target).map_err(|e| e.to_string())?;
-            Ok(())
-        }
-        Err(e) => {
-            // Provide more helpful error message
-            if e.message().contains("conflict") || e.message().contains("dirty") {
-                Err("Cannot checkout: You have uncommitted changes that would be overwritten. Please commit or stash your changes first.".to_string())
-            } else {
-                Err(format!(
-                    "Failed to checkout branch '{}': {}",
-                    branch_name, e
-                ))
-            }
-        }
-    }
+#[tauri::command]
+pub fn fetch_remote(repo_path: String, remote_name: String) -> Result<Vec<RefUpdate>, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let mut remote = repo
+        .find_remote(&remote_name)
+        .map_err(|e| format!("Remote '{}' not found: {}", remote_name, e))?;
+
+    // libgit2 reports every ref it moved, which tells the UI which commits are new
+    let updates = std::cell::RefCell::new(Vec::new());
+    let mut callbacks = remote_callbacks(&repo)?;
+    callbacks.update_tips(|ref_name, old_oid, new_oid| {
+        updates.borrow_mut().push(RefUpdate {
+            ref_name: ref_name.to_string(),
+            old_oid: (!old_oid.is_zero()).then(|| old_oid.to_string()),
+            new_oid: new_oid.to_string(),
+        });
+        true
+    });
+
+    let mut fetch_options = git2::FetchOptions::new();
+    fetch_options.remote_callbacks(callbacks);
+
+    // No refspecs: use the remote's configured ones, updating all tracking branches
+    remote
+        .fetch(&[] as &[&str], Some(&mut fetch_options), None)
+        .map_err(|e| format!("Failed to fetch from remote: {}", e))?;
+    drop(fetch_options);
+
+    Ok(updates.into_inner())
 }
 
-// Helper function to checkout a commit by OID (detached HEAD)
-fn checkout_commit_by_oid(repo: &Repository, oid: Oid, _repo_path: &str) -> Result<(), String> {
-    let obj = repo.find_object(oid, None).map_err(|e| e.to_string())?;
-    let mut checkout_builder = git2::build::CheckoutBuilder::new();
+// Asks the remote for its ref tips (like `git ls-remote`) without downloading any objects.
+// True when a branch that already has a remote-tracking ref moved since the last fetch.
+#[tauri::command]
+pub fn check_remote_updates(repo_path: String, remote: String) -> Result<bool, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let mut remote_obj = repo
+        .find_remote(&remote)
+        .map_err(|e| format!("Remote '{}' not found: {}", remote, e))?;
 
-    match repo.checkout_tree(&obj, Some(&mut checkout_builder)) {
-        Ok(()) => {
-            // Update HEAD to point to this commit (detached HEAD state)
-            repo.set_head_detached(oid).map_err(|e| e.to_string())?;
-            Ok(())
-        }
-        Err(e) => {
-            if e.message().contains("conflict") || e.message().contains("dirty") {
-                Err("Cannot checkout: You have uncommitted changes that would be overwritten. Please commit or stash your changes first.".to_string())
-            } else {
-                Err(format!("Failed to checkout commit: {}", e))
+    let callbacks = remote_callbacks(&repo)?;
+    let heads: Vec<(String, Oid)> = {
+        let connection = remote_obj
+            .connect_auth(git2::Direction::Fetch, Some(callbacks), None)
+            .map_err(|e| format!("Failed to connect to remote: {}", e))?;
+        connection
+            .list()
+            .map_err(|e| format!("Failed to list remote refs: {}", e))?
+            .iter()
+            .map(|head| (head.name().to_string(), head.oid()))
+            .collect()
+    };
+
+    let refspecs: Vec<git2::Refspec> = remote_obj
+        .refspecs()
+        .filter(|refspec| refspec.direction() == git2::Direction::Fetch)
+        .collect();
+
+    for (name, remote_oid) in heads {
+        // The configured fetch refspecs say which local ref tracks this one
+        let tracking_name = refspecs.iter().find_map(|refspec| {
+            if !refspec.src_matches(&name) {
+                return None;
             }
+            let tracking = refspec.transform(&name).ok()?;
+            tracking.as_str().map(|tracking| tracking.to_string())
+        });
+        let local_oid = tracking_name.and_then(|tracking| repo.refname_to_id(&tracking).ok());
+        if local_oid.is_some_and(|oid| oid != remote_oid) {
+            return Ok(true);
         }
     }
+
+    Ok(false)
 }
 
 #[tauri::command]
@@ -684,21 +5763,199 @@ pub fn pull_branch(repo_path: String, branch_name: String) -> Result<(), String>
             .set_target(remote_commit.id(), "Fast-forward merge")
             .map_err(|e| format!("Failed to fast-forward: {}", e))?;
 
-        // Update working directory
+        // Update working directory
+        let obj = repo
+            .find_object(remote_commit.id(), None)
+            .map_err(|e| e.to_string())?;
+        let mut checkout_builder = git2::build::CheckoutBuilder::new();
+        repo.checkout_tree(&obj, Some(&mut checkout_builder))
+            .map_err(|e| format!("Failed to checkout updated branch: {}", e))?;
+
+        Ok(())
+    } else if analysis.0.is_normal() {
+        // Normal merge required - for simplicity, we'll just error and ask user to merge manually
+        Err("Merge required. Please merge manually.".to_string())
+    } else {
+        Err("No changes to pull.".to_string())
+    }
+}
+
+#[tauri::command]
+pub fn fast_forward(repo_path: String, branch: String, target: String) -> Result<(), String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    let mut local_branch = repo
+        .find_branch(&branch, BranchType::Local)
+        .map_err(|e| format!("Branch not found: {}", e))?;
+    let branch_oid = local_branch
+        .get()
+        .target()
+        .ok_or_else(|| "Branch has no target commit".to_string())?;
+
+    // Target can be a commit ID or any ref name (e.g. origin/main)
+    let target_commit = repo
+        .revparse_single(&target)
+        .and_then(|obj| obj.peel_to_commit())
+        .map_err(|e| format!("Target '{}' not found: {}", target, e))?;
+    let target_oid = target_commit.id();
+
+    if branch_oid == target_oid {
+        return Err(format!("Branch '{}' is already up to date", branch));
+    }
+
+    let is_descendant = repo
+        .graph_descendant_of(target_oid, branch_oid)
+        .map_err(|e| e.to_string())?;
+    if !is_descendant {
+        return Err(format!(
+            "Cannot fast-forward '{}' to '{}': the branches have diverged",
+            branch, target
+        ));
+    }
+
+    // Update the working tree first so a dirty checkout doesn't leave the ref moved
+    if local_branch.is_head() {
         let obj = repo
-            .find_object(remote_commit.id(), None)
+            .find_object(target_oid, None)
             .map_err(|e| e.to_string())?;
         let mut checkout_builder = git2::build::CheckoutBuilder::new();
         repo.checkout_tree(&obj, Some(&mut checkout_builder))
-            .map_err(|e| format!("Failed to checkout updated branch: {}", e))?;
+            .map_err(|e| {
+                if e.message().contains("conflict") || e.message().contains("dirty") {
+                    "Cannot fast-forward: You have uncommitted changes that would be overwritten. Please commit or stash your changes first.".to_string()
+                } else {
+                    format!("Failed to checkout updated branch: {}", e)
+                }
+            })?;
+    }
 
-        Ok(())
-    } else if analysis.0.is_normal() {
-        // Normal merge required - for simplicity, we'll just error and ask user to merge manually
-        Err("Merge required. Please merge manually.".to_string())
-    } else {
-        Err("No changes to pull.".to_string())
+    let log_message = format!("fast-forward: {} to {}", branch, target);
+    local_branch
+        .get_mut()
+        .set_target(target_oid, &log_message)
+        .map_err(|e| format!("Failed to fast-forward: {}", e))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_divergence(repo_path: String, branch: String) -> Result<DivergenceInfo, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    let local_branch = repo
+        .find_branch(&branch, BranchType::Local)
+        .map_err(|e| format!("Branch not found: {}", e))?;
+    let upstream = local_branch.upstream().map_err(|e| {
+        if e.code() == git2::ErrorCode::NotFound {
+            format!("NoUpstream: Branch '{}' has no upstream configured", branch)
+        } else {
+            e.to_string()
+        }
+    })?;
+
+    let branch_oid = local_branch
+        .get()
+        .target()
+        .ok_or_else(|| "Branch has no target commit".to_string())?;
+    let upstream_oid = upstream
+        .get()
+        .target()
+        .ok_or_else(|| "Upstream has no target commit".to_string())?;
+
+    // Unrelated histories have no merge base, which is not an error for visualization
+    let merge_base = match repo.merge_base(branch_oid, upstream_oid) {
+        Ok(oid) => Some(oid.to_string()),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => None,
+        Err(e) => return Err(e.to_string()),
+    };
+    let (ahead, behind) = repo
+        .graph_ahead_behind(branch_oid, upstream_oid)
+        .map_err(|e| e.to_string())?;
+
+    Ok(DivergenceInfo {
+        branch,
+        upstream: upstream.name().ok().flatten().unwrap_or("").to_string(),
+        merge_base,
+        ahead,
+        behind,
+    })
+}
+
+#[tauri::command]
+pub fn get_branch_tracking(repo_path: String) -> Result<Vec<BranchTracking>, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    let mut tracking = Vec::new();
+    for branch in repo
+        .branches(Some(BranchType::Local))
+        .map_err(|e| e.to_string())?
+    {
+        let (branch, _) = branch.map_err(|e| e.to_string())?;
+        let name = match branch.name() {
+            Ok(Some(name)) => name.to_string(),
+            _ => continue,
+        };
+
+        // A configured upstream that was deleted on the remote counts as no upstream
+        let upstream = branch.upstream().ok();
+        let upstream_oid = upstream
+            .as_ref()
+            .and_then(|upstream| upstream.get().target());
+        let ahead_behind = match (branch.get().target(), upstream_oid) {
+            (Some(local_oid), Some(upstream_oid)) => Some(
+                repo.graph_ahead_behind(local_oid, upstream_oid)
+                    .map_err(|e| e.to_string())?,
+            ),
+            _ => None,
+        };
+
+        tracking.push(BranchTracking {
+            branch: name,
+            upstream: upstream
+                .as_ref()
+                .and_then(|upstream| upstream.name().ok().flatten())
+                .map(|name| name.to_string()),
+            ahead_behind,
+        });
     }
+
+    Ok(tracking)
+}
+
+// Commits on each side since the merge base of `a` and `b`, plus the merge base itself
+#[tauri::command]
+pub fn get_divergence_sizes(
+    repo_path: String,
+    a: String,
+    b: String,
+) -> Result<(usize, usize, String), String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    // Either side can be a commit ID or any ref name
+    let resolve = |spec: &str| {
+        repo.revparse_single(spec)
+            .and_then(|obj| obj.peel_to_commit())
+            .map(|commit| commit.id())
+            .map_err(|e| format!("'{}' not found: {}", spec, e))
+    };
+    let a_oid = resolve(&a)?;
+    let b_oid = resolve(&b)?;
+
+    let merge_base = repo.merge_base(a_oid, b_oid).map_err(|e| {
+        if e.code() == git2::ErrorCode::NotFound {
+            format!(
+                "UnrelatedHistories: '{}' and '{}' have no common ancestor",
+                a, b
+            )
+        } else {
+            e.to_string()
+        }
+    })?;
+    let (a_count, b_count) = repo
+        .graph_ahead_behind(a_oid, b_oid)
+        .map_err(|e| e.to_string())?;
+
+    Ok((a_count, b_count, merge_base.to_string()))
 }
 
 #[tauri::command]
@@ -727,7 +5984,7 @@ pub fn push_branch(repo_path: String, branch_name: String) -> Result<(), String>
 }
 
 #[tauri::command]
-pub fn delete_branch(repo_path: String, branch_name: String) -> Result<(), String> {
+pub fn delete_branch(repo_path: String, branch_name: String, force: bool) -> Result<(), String> {
     let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
 
     // Try to find the local branch
@@ -742,6 +5999,28 @@ pub fn delete_branch(repo_path: String, branch_name: String) -> Result<(), Strin
         );
     }
 
+    // Like `git branch -d`, only delete without force when HEAD already contains the branch
+    if !force {
+        let branch_oid = branch
+            .get()
+            .target()
+            .ok_or_else(|| "Branch has no target commit".to_string())?;
+        let head_oid = repo.head().ok().and_then(|head| head.target());
+        let merged = match head_oid {
+            Some(head_oid) => repo
+                .merge_base(branch_oid, head_oid)
+                .map(|base| base == branch_oid)
+                .unwrap_or(false),
+            None => false,
+        };
+        if !merged {
+            return Err(format!(
+                "NotMerged: branch '{}' is not fully merged into HEAD. Use force to delete it anyway.",
+                branch_name
+            ));
+        }
+    }
+
     // Delete the branch
     branch
         .delete()
@@ -750,14 +6029,160 @@ pub fn delete_branch(repo_path: String, branch_name: String) -> Result<(), Strin
     Ok(())
 }
 
+// Deletes every local branch fully merged into `target`, returning the deleted names.
+// The current branch, `target` itself and the names in `exclude` are always kept.
+// If some deletions fail, the error lists both the deleted branches and the failures.
+#[tauri::command]
+pub fn delete_merged_branches(
+    repo_path: String,
+    target: String,
+    exclude: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let target_oid = repo
+        .revparse_single(&target)
+        .and_then(|object| object.peel_to_commit())
+        .map(|commit| commit.id())
+        .map_err(|e| format!("NotFound: '{}' is not a commit: {}", target, e))?;
+
+    // Collected first, deleting refs while iterating over them isn't safe
+    let mut merged = Vec::new();
+    for entry in repo
+        .branches(Some(BranchType::Local))
+        .map_err(|e| e.to_string())?
+    {
+        let (branch, _) = entry.map_err(|e| e.to_string())?;
+        let name = match branch.name() {
+            Ok(Some(name)) => name.to_string(),
+            _ => continue,
+        };
+        if branch.is_head() || name == target || exclude.contains(&name) {
+            continue;
+        }
+        let Some(tip) = branch.get().target() else {
+            continue;
+        };
+        let is_merged = tip == target_oid
+            || repo
+                .graph_descendant_of(target_oid, tip)
+                .map_err(|e| e.to_string())?;
+        if is_merged {
+            merged.push(name);
+        }
+    }
+
+    // Keep going after a failure so the error can say exactly what is already gone
+    let mut deleted = Vec::new();
+    let mut failures = Vec::new();
+    for name in merged {
+        match repo
+            .find_branch(&name, BranchType::Local)
+            .and_then(|mut branch| branch.delete())
+        {
+            Ok(()) => deleted.push(name),
+            Err(e) => failures.push(format!("'{}': {}", name, e)),
+        }
+    }
+
+    if !failures.is_empty() {
+        let deleted = if deleted.is_empty() {
+            "no branches".to_string()
+        } else {
+            deleted.join(", ")
+        };
+        return Err(format!(
+            "Deleted {}, but failed to delete {}",
+            deleted,
+            failures.join("; ")
+        ));
+    }
+
+    Ok(deleted)
+}
+
+#[tauri::command]
+pub fn is_valid_ref_name(name: String, kind: String) -> Result<RefNameValidation, String> {
+    let reason = ref_name_problem(&name, &kind)?;
+
+    Ok(RefNameValidation {
+        valid: reason.is_none(),
+        reason,
+    })
+}
+
+// Helper function to explain why `name` can't be used for a ref of `kind` ("branch" or "tag"),
+// following the rules of `git check-ref-format`. Returns None when the name is valid
+fn ref_name_problem(name: &str, kind: &str) -> Result<Option<String>, String> {
+    let prefix = match kind {
+        "branch" => "refs/heads/",
+        "tag" => "refs/tags/",
+        _ => {
+            return Err(format!(
+                "Invalid ref kind '{}'. Expected \"branch\" or \"tag\"",
+                kind
+            ))
+        }
+    };
+
+    let problem = if name.is_empty() {
+        Some("Name cannot be empty".to_string())
+    } else if let Some(c) = name
+        .chars()
+        .find(|c| c.is_control() || " ~^:?*[\\".contains(*c))
+    {
+        Some(if c.is_control() || c == ' ' {
+            "Name cannot contain spaces or control characters".to_string()
+        } else {
+            format!("Name cannot contain '{}'", c)
+        })
+    } else if name.contains("..") {
+        Some("Name cannot contain '..'".to_string())
+    } else if name.contains("@{") {
+        Some("Name cannot contain '@{'".to_string())
+    } else if name == "@" {
+        Some("Name cannot be '@'".to_string())
+    } else if name.starts_with('-') {
+        // Would be mistaken for an option on the command line
+        Some("Name cannot start with '-'".to_string())
+    } else if name.starts_with('/') || name.ends_with('/') || name.contains("//") {
+        Some("Name cannot start or end with '/' or contain '//'".to_string())
+    } else if name.ends_with('.') {
+        Some("Name cannot end with '.'".to_string())
+    } else if name.split('/').any(|part| part.starts_with('.')) {
+        Some("Name parts cannot start with '.'".to_string())
+    } else if name.split('/').any(|part| part.ends_with(".lock")) {
+        Some("Name parts cannot end with '.lock'".to_string())
+    } else if kind == "branch" && name == "HEAD" {
+        Some("'HEAD' is not a valid branch name".to_string())
+    } else if !git2::Reference::is_valid_name(&format!("{}{}", prefix, name)) {
+        // Anything libgit2 rejects that the rules above didn't catch
+        Some(format!("'{}' is not a valid {} name", name, kind))
+    } else {
+        None
+    };
+
+    Ok(problem)
+}
+
 #[tauri::command]
 pub fn create_branch(
     repo_path: String,
     branch_name: String,
     commit_id: String,
+    checkout: bool,
 ) -> Result<(), String> {
     let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
 
+    if let Some(problem) = ref_name_problem(&branch_name, "branch")? {
+        return Err(format!(
+            "Invalid branch name '{}': {}",
+            branch_name, problem
+        ));
+    }
+    if repo.find_branch(&branch_name, BranchType::Local).is_ok() {
+        return Err(format!("Branch '{}' already exists", branch_name));
+    }
+
     // Parse the commit ID to OID
     let oid = Oid::from_str(&commit_id).map_err(|e| format!("Invalid commit ID: {}", e))?;
 
@@ -767,9 +6192,176 @@ pub fn create_branch(
         .map_err(|e| format!("Commit not found: {}", e))?;
 
     // Create the new branch
-    repo.branch(&branch_name, &commit, false)
+    let mut branch = repo
+        .branch(&branch_name, &commit, false)
         .map_err(|e| format!("Failed to create branch: {}", e))?;
 
+    if checkout {
+        // Safe checkout refuses to overwrite local changes
+        let mut checkout_builder = git2::build::CheckoutBuilder::new();
+        let result = repo
+            .checkout_tree(commit.as_object(), Some(&mut checkout_builder))
+            .and_then(|_| repo.set_head(&format!("refs/heads/{}", branch_name)));
+
+        if let Err(e) = result {
+            // Don't leave a half-finished branch behind when the checkout fails
+            let _ = branch.delete();
+            return Err(format!(
+                "Failed to checkout new branch '{}': {}",
+                branch_name, e
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// Creates an annotated tag when `message` is given, otherwise a lightweight tag
+#[tauri::command]
+pub fn create_tag(
+    repo_path: String,
+    tag_name: String,
+    commit_id: String,
+    message: Option<String>,
+    force: Option<bool>,
+) -> Result<(), String> {
+    let force = force.unwrap_or(false);
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    if let Some(problem) = ref_name_problem(&tag_name, "tag")? {
+        return Err(format!("Invalid tag name '{}': {}", tag_name, problem));
+    }
+    if !force
+        && repo
+            .find_reference(&format!("refs/tags/{}", tag_name))
+            .is_ok()
+    {
+        return Err(format!("Tag '{}' already exists", tag_name));
+    }
+
+    let oid = Oid::from_str(&commit_id).map_err(|e| format!("Invalid commit ID: {}", e))?;
+    let commit = repo
+        .find_commit(oid)
+        .map_err(|e| format!("Commit not found: {}", e))?;
+
+    match message {
+        Some(message) => {
+            let tagger = default_signature(&repo)?;
+            repo.tag(&tag_name, commit.as_object(), &tagger, &message, force)
+        }
+        None => repo.tag_lightweight(&tag_name, commit.as_object(), force),
+    }
+    .map_err(|e| format!("Failed to create tag: {}", e))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_tag(repo_path: String, tag_name: String) -> Result<(), String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    // get_commits passes tag refs through unchanged, so accept the full ref name too
+    let tag_name = tag_name
+        .strip_prefix("refs/tags/")
+        .unwrap_or(&tag_name)
+        .to_string();
+
+    if repo
+        .find_reference(&format!("refs/tags/{}", tag_name))
+        .is_err()
+    {
+        return Err(format!("Tag '{}' not found", tag_name));
+    }
+
+    repo.tag_delete(&tag_name)
+        .map_err(|e| format!("Failed to delete tag: {}", e))?;
+
+    Ok(())
+}
+
+// Replaces a lightweight tag with an annotated one pointing at the same object
+#[tauri::command]
+pub fn annotate_tag(repo_path: String, tag_name: String, message: String) -> Result<(), String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    let tag_name = tag_name
+        .strip_prefix("refs/tags/")
+        .unwrap_or(&tag_name)
+        .to_string();
+    let reference = repo
+        .find_reference(&format!("refs/tags/{}", tag_name))
+        .map_err(|_| format!("Tag '{}' not found", tag_name))?;
+    let target_oid = reference
+        .target()
+        .ok_or_else(|| format!("Tag '{}' has no target", tag_name))?;
+    let target = repo
+        .find_object(target_oid, None)
+        .map_err(|e| e.to_string())?;
+    if target.kind() == Some(git2::ObjectType::Tag) {
+        return Err(format!("Tag '{}' is already annotated", tag_name));
+    }
+
+    // Overwriting in place means the tag never disappears, even if creating the new one fails
+    let tagger = default_signature(&repo)?;
+    repo.tag(&tag_name, &target, &tagger, &message, true)
+        .map_err(|e| format!("Failed to annotate tag: {}", e))?;
+
+    Ok(())
+}
+
+// Points an existing tag at another commit (`git tag -f`), keeping the message of annotated tags.
+// Moving a tag that points elsewhere requires force
+#[tauri::command]
+pub fn move_tag(
+    repo_path: String,
+    tag_name: String,
+    new_target_commit_id: String,
+    force: bool,
+) -> Result<(), String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+
+    let tag_name = tag_name
+        .strip_prefix("refs/tags/")
+        .unwrap_or(&tag_name)
+        .to_string();
+    let reference = repo
+        .find_reference(&format!("refs/tags/{}", tag_name))
+        .map_err(|_| format!("Tag '{}' not found", tag_name))?;
+    let target_oid = reference
+        .target()
+        .ok_or_else(|| format!("Tag '{}' has no target", tag_name))?;
+
+    let oid =
+        Oid::from_str(&new_target_commit_id).map_err(|e| format!("Invalid commit ID: {}", e))?;
+    let commit = repo
+        .find_commit(oid)
+        .map_err(|e| format!("Commit not found: {}", e))?;
+
+    // Already there: nothing to overwrite, so force isn't needed
+    let current = reference.peel_to_commit().map_err(|e| e.to_string())?;
+    if current.id() == oid {
+        return Ok(());
+    }
+    if !force {
+        return Err(format!(
+            "Tag '{}' points at {}. Moving it to {} requires force",
+            tag_name,
+            short_id(current.id()),
+            short_id(oid)
+        ));
+    }
+
+    match repo.find_tag(target_oid) {
+        // Annotated: the old tag object stays behind unreferenced, like with git tag -f
+        Ok(old_tag) => {
+            let message = old_tag.message().unwrap_or("").to_string();
+            let tagger = default_signature(&repo)?;
+            repo.tag(&tag_name, commit.as_object(), &tagger, &message, true)
+        }
+        Err(_) => repo.tag_lightweight(&tag_name, commit.as_object(), true),
+    }
+    .map_err(|e| format!("Failed to move tag: {}", e))?;
+
     Ok(())
 }
 
@@ -802,6 +6394,34 @@ pub fn delete_remote_branch(
     Ok(())
 }
 
+// Stashes the uncommitted changes and returns the OID of the new stash commit
+#[tauri::command]
+pub fn create_stash(
+    repo_path: String,
+    message: Option<String>,
+    include_untracked: bool,
+) -> Result<String, String> {
+    let mut repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
+    let signature = default_signature(&repo)?;
+
+    let mut flags = git2::StashFlags::DEFAULT;
+    flags.set(git2::StashFlags::INCLUDE_UNTRACKED, include_untracked);
+
+    // An empty message gets git's default "WIP on <branch>: ..." message
+    let message = message.filter(|m| !m.trim().is_empty());
+    let oid = repo
+        .stash_save2(&signature, message.as_deref(), Some(flags))
+        .map_err(|e| {
+            if e.code() == git2::ErrorCode::NotFound {
+                "NothingToStash: There are no local changes to stash".to_string()
+            } else {
+                format!("Failed to stash changes: {}", e)
+            }
+        })?;
+
+    Ok(oid.to_string())
+}
+
 #[tauri::command]
 pub fn apply_stash(repo_path: String, stash_ref: String) -> Result<(), String> {
     let mut repo = Repository::open(&repo_path).map_err(|e| e.to_string())?;
@@ -809,12 +6429,7 @@ pub fn apply_stash(repo_path: String, stash_ref: String) -> Result<(), String> {
     // Parse stash index from stash_ref (e.g., "stash@{0}")
     let index = parse_stash_index(&stash_ref)?;
     
-    // Apply the stash
-    let mut options = git2::StashApplyOptions::new();
-    repo.stash_apply(index, Some(&mut options))
-        .map_err(|e| format!("Failed to apply stash {}: {}", stash_ref, e))?;
-    
-    Ok(())
+    apply_stash_at(&mut repo, index, false)
 }
 
 #[tauri::command]
@@ -824,11 +6439,18 @@ pub fn drop_stash(repo_path: String, stash_ref: String) -> Result<(), String> {
     // Parse stash index from stash_ref (e.g., "stash@{0}")
     let index = parse_stash_index(&stash_ref)?;
     
-    // Drop the stash
-    repo.stash_drop(index)
-        .map_err(|e| format!("Failed to drop stash {}: {}", stash_ref, e))?;
-    
-    Ok(())
+    drop_stash_at(&mut repo, index)
+}
+
+// Helper function to drop stash@{index}
+fn drop_stash_at(repo: &mut Repository, index: usize) -> Result<(), String> {
+    repo.stash_drop(index).map_err(|e| {
+        if e.code() == git2::ErrorCode::NotFound {
+            format!("NotFound: stash@{{{}}} does not exist", index)
+        } else {
+            format!("Failed to drop stash stash@{{{}}}: {}", index, e)
+        }
+    })
 }
 
 #[tauri::command]
@@ -838,15 +6460,55 @@ pub fn pop_stash(repo_path: String, stash_ref: String) -> Result<(), String> {
     // Parse stash index from stash_ref (e.g., "stash@{0}")
     let index = parse_stash_index(&stash_ref)?;
     
-    // Apply the stash
+    apply_stash_at(&mut repo, index, true)
+}
+
+// Helper function to apply stash@{index}. With `pop` the stash is dropped afterwards,
+// but only when it applied without conflicts so nothing is lost
+fn apply_stash_at(repo: &mut Repository, index: usize, pop: bool) -> Result<(), String> {
+    let stash_ref = format!("stash@{{{}}}", index);
+
+    // The default safe checkout refuses to overwrite local changes
     let mut options = git2::StashApplyOptions::new();
-    repo.stash_apply(index, Some(&mut options))
-        .map_err(|e| format!("Failed to apply stash {}: {}", stash_ref, e))?;
-    
-    // If apply succeeded, drop the stash
-    repo.stash_drop(index)
-        .map_err(|e| format!("Failed to drop stash after applying {}: {}", stash_ref, e))?;
-    
+    match repo.stash_apply(index, Some(&mut options)) {
+        Ok(()) => {}
+        Err(e) if e.code() == git2::ErrorCode::NotFound => {
+            return Err(format!("NotFound: {} does not exist", stash_ref));
+        }
+        // Only checkout conflicts mean local changes are in the way
+        Err(e)
+            if e.code() == git2::ErrorCode::Conflict && e.class() == git2::ErrorClass::Checkout =>
+        {
+            return Err(format!(
+                "DirtyWorkTree: applying {} would overwrite local changes. Please commit or stash them first. ({})",
+                stash_ref,
+                e.message()
+            ));
+        }
+        Err(e) if e.code() == git2::ErrorCode::Conflict => {
+            return Err(format!(
+                "Conflict: {} could not be applied cleanly ({}). The stash was kept.",
+                stash_ref,
+                e.message()
+            ));
+        }
+        Err(e) => return Err(format!("Failed to apply stash {}: {}", stash_ref, e)),
+    }
+
+    let repo_index = repo.index().map_err(|e| e.to_string())?;
+    if repo_index.has_conflicts() {
+        return Err(format!(
+            "Conflict: {} was applied with conflicts in {}. The stash was kept.",
+            stash_ref,
+            conflicted_paths(&repo_index)?.join(", ")
+        ));
+    }
+
+    if pop {
+        repo.stash_drop(index)
+            .map_err(|e| format!("Failed to drop stash after applying {}: {}", stash_ref, e))?;
+    }
+
     Ok(())
 }
 
@@ -868,3 +6530,310 @@ fn parse_stash_index(stash_ref: &str) -> Result<usize, String> {
     index_str.parse::<usize>()
         .map_err(|e| format!("Invalid stash index in {}: {}", stash_ref, e))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A scratch repository under the temp dir, removed again when dropped
+    struct TempRepo {
+        path: std::path::PathBuf,
+        repo: Repository,
+    }
+
+    impl TempRepo {
+        fn new() -> Self {
+            static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+            let path = std::env::temp_dir().join(format!(
+                "super-git-graph-test-{}-{}",
+                std::process::id(),
+                COUNTER.fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+            ));
+            let _ = fs::remove_dir_all(&path);
+            let repo = Repository::init(&path).unwrap();
+            let mut config = repo.config().unwrap();
+            config.set_str("user.name", "Default User").unwrap();
+            config.set_str("user.email", "default@example.com").unwrap();
+            TempRepo { path, repo }
+        }
+
+        fn path_string(&self) -> String {
+            self.path.to_string_lossy().to_string()
+        }
+
+        // Writes `file` and commits it on HEAD with `author` as both author and committer
+        fn commit_file(&self, file: &str, content: &str, message: &str, author: &str) -> Oid {
+            fs::write(self.path.join(file), content).unwrap();
+            let mut index = self.repo.index().unwrap();
+            index.add_path(std::path::Path::new(file)).unwrap();
+            index.write().unwrap();
+            let tree = self.repo.find_tree(index.write_tree().unwrap()).unwrap();
+
+            let email = format!("{}@example.com", author.to_lowercase());
+            let signature = git2::Signature::now(author, &email).unwrap();
+            let parent = self.repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            self.repo
+                .commit(
+                    Some("HEAD"),
+                    &signature,
+                    &signature,
+                    message,
+                    &tree,
+                    &parents,
+                )
+                .unwrap()
+        }
+    }
+
+    impl Drop for TempRepo {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn ref_name_problem_accepts_valid_names() {
+        for name in ["main", "feature/login", "v1.0.0", "fix-123_a"] {
+            assert_eq!(ref_name_problem(name, "branch").unwrap(), None, "{}", name);
+        }
+        assert_eq!(ref_name_problem("HEAD", "tag").unwrap(), None);
+    }
+
+    #[test]
+    fn ref_name_problem_rejects_invalid_names() {
+        for name in [
+            "",
+            "has space",
+            "a..b",
+            "a@{b",
+            "@",
+            "-opt",
+            "/lead",
+            "trail/",
+            "a//b",
+            "end.",
+            "a/.hidden",
+            "x.lock",
+            "a~1",
+            "a^",
+            "a:b",
+            "a?",
+            "a*",
+            "a[b",
+            "a\\b",
+        ] {
+            assert!(
+                ref_name_problem(name, "branch").unwrap().is_some(),
+                "{:?}",
+                name
+            );
+        }
+        assert!(ref_name_problem("HEAD", "branch").unwrap().is_some());
+        assert!(ref_name_problem("main", "remote").is_err());
+    }
+
+    #[test]
+    fn parse_stash_index_reads_the_index() {
+        assert_eq!(parse_stash_index("stash@{0}").unwrap(), 0);
+        assert_eq!(parse_stash_index("stash@{12}").unwrap(), 12);
+        for stash_ref in [
+            "stash@{}",
+            "stash@{x}",
+            "stash@{-1}",
+            "stash",
+            "refs/stash",
+            "stash@{1",
+        ] {
+            assert!(parse_stash_index(stash_ref).is_err(), "{}", stash_ref);
+        }
+    }
+
+    #[test]
+    fn parse_co_authors_keeps_identity_trailers() {
+        let message = "Fix parser\n\nDetails here.\n\n\
+            Co-authored-by: Jane Doe <jane@example.com>\n\
+            co-authored-by: John <john@example.com>\n\
+            Co-authored-by: nobody\n\
+            Signed-off-by: Jane Doe <jane@example.com>\n";
+        assert_eq!(
+            parse_co_authors(message),
+            strings(&["Jane Doe <jane@example.com>", "John <john@example.com>"])
+        );
+        assert!(parse_co_authors("No trailers here").is_empty());
+    }
+
+    #[test]
+    fn path_excludes_match_names_anywhere_and_paths_from_the_root() {
+        let excludes = PathExcludes::parse(strings(&[
+            "*.lock",
+            "node_modules/",
+            "/src/generated/*",
+            " ",
+        ]))
+        .unwrap();
+        assert!(excludes.is_excluded("Cargo.lock"));
+        assert!(excludes.is_excluded("sub/dir/yarn.lock"));
+        assert!(excludes.is_excluded("node_modules/pkg/index.js"));
+        assert!(excludes.is_excluded("web/node_modules/pkg/index.js"));
+        assert!(excludes.is_excluded("src/generated/api.rs"));
+        assert!(excludes.is_excluded("src/generated/nested/api.rs"));
+        assert!(!excludes.is_excluded("lib/src/generated/api.rs"));
+        assert!(!excludes.is_excluded("src/main.rs"));
+
+        assert!(!PathExcludes::parse(vec![]).unwrap().is_excluded("anything"));
+        assert!(PathExcludes::parse(strings(&["[unclosed"])).is_err());
+    }
+
+    #[test]
+    fn ref_filter_combines_includes_and_excludes() {
+        let filter = RefFilter::parse(strings(&["origin/*", "!origin/ci-*"])).unwrap();
+        assert!(filter.is_visible("refs/remotes/origin/main", "origin/main"));
+        assert!(!filter.is_visible("refs/remotes/origin/ci-42", "origin/ci-42"));
+        assert!(!filter.is_visible("refs/heads/main", "main"));
+
+        let exclude_only = RefFilter::parse(strings(&["!refs/tags/*"])).unwrap();
+        assert!(exclude_only.is_visible("refs/heads/main", "main"));
+        assert!(!exclude_only.is_visible("refs/tags/v1", "refs/tags/v1"));
+
+        assert!(RefFilter::parse(vec![])
+            .unwrap()
+            .is_visible("refs/heads/x", "x"));
+        assert!(RefFilter::parse(strings(&["[bad"])).is_err());
+    }
+
+    #[test]
+    fn replay_commits_keeps_authors_and_applies_committer_overrides() {
+        let temp = TempRepo::new();
+        let base = temp.commit_file("a.txt", "a\n", "base", "Alice");
+        let first = temp.commit_file("b.txt", "b\n", "first", "Bob");
+        let second = temp.commit_file("c.txt", "c\n", "second", "Carol");
+
+        let pair = git2::Signature::now("Pair", "pair@example.com").unwrap();
+        let steps = vec![
+            ReplayStep {
+                oid: first,
+                message: Some("first, reworded".to_string()),
+                author: None,
+                committer: Some(pair),
+                squash: false,
+            },
+            ReplayStep::pick(second),
+        ];
+        let onto = temp.repo.find_commit(base).unwrap();
+        let committer = default_signature(&temp.repo).unwrap();
+        let new_oids = replay_commits(&temp.repo, onto, &steps, &committer).unwrap();
+        assert_eq!(new_oids.len(), 2);
+
+        let reworded = temp.repo.find_commit(new_oids[0]).unwrap();
+        assert_eq!(reworded.message(), Some("first, reworded"));
+        assert_eq!(reworded.author().name(), Some("Bob"));
+        assert_eq!(reworded.committer().name(), Some("Pair"));
+        assert_eq!(reworded.parent_id(0).unwrap(), base);
+
+        let picked = temp.repo.find_commit(new_oids[1]).unwrap();
+        assert_eq!(picked.message(), Some("second"));
+        assert_eq!(picked.author().name(), Some("Carol"));
+        assert_eq!(picked.committer().name(), Some("Default User"));
+        assert_eq!(picked.parent_id(0).unwrap(), new_oids[0]);
+        assert_eq!(
+            picked.tree_id(),
+            temp.repo.find_commit(second).unwrap().tree_id()
+        );
+    }
+
+    #[test]
+    fn replay_commits_squash_folds_into_the_previous_commit() {
+        let temp = TempRepo::new();
+        let base = temp.commit_file("a.txt", "a\n", "base", "Alice");
+        let first = temp.commit_file("b.txt", "b\n", "first", "Bob");
+        let second = temp.commit_file("c.txt", "c\n", "second", "Carol");
+
+        let steps = vec![
+            ReplayStep::pick(first),
+            ReplayStep {
+                oid: second,
+                message: Some("both".to_string()),
+                author: None,
+                committer: None,
+                squash: true,
+            },
+        ];
+        let onto = temp.repo.find_commit(base).unwrap();
+        let committer = default_signature(&temp.repo).unwrap();
+        let new_oids = replay_commits(&temp.repo, onto, &steps, &committer).unwrap();
+        assert_eq!(new_oids.len(), 1);
+
+        let squashed = temp.repo.find_commit(new_oids[0]).unwrap();
+        assert_eq!(squashed.message(), Some("both"));
+        assert_eq!(squashed.author().name(), Some("Bob"));
+        assert_eq!(squashed.parent_id(0).unwrap(), base);
+        assert_eq!(
+            squashed.tree_id(),
+            temp.repo.find_commit(second).unwrap().tree_id()
+        );
+    }
+
+    #[test]
+    fn reword_commit_only_overrides_the_committer_of_the_reworded_commit() {
+        let temp = TempRepo::new();
+        temp.commit_file("a.txt", "a\n", "base", "Alice");
+        let target = temp.commit_file("b.txt", "b\n", "typo", "Bob");
+        temp.commit_file("c.txt", "c\n", "later", "Carol");
+
+        reword_commit(
+            temp.path_string(),
+            target.to_string(),
+            "fixed".to_string(),
+            None,
+            Some(("Pair".to_string(), "pair@example.com".to_string())),
+        )
+        .unwrap();
+
+        let head = temp.repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.message(), Some("later"));
+        assert_eq!(head.committer().name(), Some("Default User"));
+        let reworded = head.parent(0).unwrap();
+        assert_eq!(reworded.message(), Some("fixed"));
+        assert_eq!(reworded.author().name(), Some("Bob"));
+        assert_eq!(reworded.committer().name(), Some("Pair"));
+    }
+
+    #[test]
+    fn get_commits_skip_and_limit_count_filtered_commits() {
+        let temp = TempRepo::new();
+        for i in 0..6 {
+            let kind = if i % 2 == 0 { "feat" } else { "chore" };
+            temp.commit_file("f.txt", &i.to_string(), &format!("{} {}", kind, i), "Alice");
+        }
+
+        let page = |skip, limit| {
+            let response = get_commits(
+                temp.path_string(),
+                limit,
+                Some(skip),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some("feat".to_string()),
+                None,
+                None,
+            )
+            .unwrap();
+            let messages: Vec<String> = response.commits.into_iter().map(|c| c.message).collect();
+            (messages, response.has_more)
+        };
+
+        assert_eq!(page(0, 2), (strings(&["feat 4", "feat 2"]), true));
+        assert_eq!(page(2, 2), (strings(&["feat 0"]), false));
+    }
+}
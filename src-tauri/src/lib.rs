@@ -37,27 +37,119 @@ fn reveal_store_file<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // Verbosity is controlled with RUST_LOG, e.g. RUST_LOG=debug. Only errors are shown by default
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("error")).init();
+
+    let handler = tauri::generate_handler![
+        git::set_read_only,
+        git::get_commits,
+        git::get_commits_compact,
+        git::get_ancestry,
+        git::get_connecting_commits,
+        git::search_commits,
+        git::get_commit_details,
+        git::get_signatures,
+        git::blame_range,
+        git::author_commit_counts,
+        git::get_commit_trend,
+        git::get_default_signature,
+        git::export_graph_svg,
+        git::find_large_blobs,
+        git::get_commit_time_range,
+        git::get_odb_stats,
+        git::find_dangling_commits,
+        git::find_fixup_commits,
+        git::find_equivalent_commits,
+        git::get_all_refs,
+        git::resolve_ref_full,
+        git::refs_at,
+        git::get_diff,
+        git::diff_workdir_to_commit,
+        git::get_diff_tree_summary,
+        git::get_diff_hunks,
+        git::get_blob_oid,
+        git::get_commit_tree_oid,
+        git::get_tree_file_count,
+        git::get_staged_paths,
+        git::get_staged_file_diff,
+        git::get_working_diff,
+        git::commit,
+        git::get_repo_description,
+        git::set_repo_description,
+        git::is_path_ignored,
+        git::force_add_path,
+        git::stage_file,
+        git::unstage_file,
+        git::get_three_way_diff,
+        git::resolve_conflict_with,
+        git::get_merge_status,
+        git::get_rebase_progress,
+        git::rebase_continue,
+        git::materialize_tree,
+        git::archive_commit,
+        git::get_submodule_status,
+        git::get_submodule_commit_log,
+        git::update_submodule,
+        git::checkout_commit,
+        git::checkout_branch,
+        git::switch_branch_autostash,
+        git::get_head_reflog,
+        git::undo_commits,
+        git::reorder_commits,
+        git::squash_commits,
+        git::drop_commit,
+        git::reword_commit,
+        git::cherry_pick_range,
+        git::can_modify_history,
+        git::get_remotes,
+        git::fetch_remote,
+        git::check_remote_updates,
+        git::pull_branch,
+        git::fast_forward,
+        git::get_divergence,
+        git::get_divergence_sizes,
+        git::get_branch_tracking,
+        git::push_branch,
+        git::is_valid_ref_name,
+        git::create_branch,
+        git::create_tag,
+        git::delete_tag,
+        git::annotate_tag,
+        git::move_tag,
+        git::delete_branch,
+        git::delete_merged_branches,
+        git::delete_remote_branch,
+        git::create_stash,
+        git::apply_stash,
+        git::drop_stash,
+        git::pop_stash,
+        reveal_store_file
+    ];
+
     tauri::Builder::default()
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(tauri_plugin_dialog::init())
-        .invoke_handler(tauri::generate_handler![
-            git::get_commits,
-            git::get_all_refs,
-            git::get_diff,
-            git::checkout_commit,
-            git::checkout_branch,
-            git::pull_branch,
-            git::push_branch,
-            git::create_branch,
-            git::delete_branch,
-            git::delete_remote_branch,
-            git::apply_stash,
-            git::drop_stash,
-            git::pop_stash,
-            reveal_store_file
-        ])
+        .manage(git::ReadOnlyMode::default())
+        .invoke_handler(move |invoke| {
+            // Mutating commands are rejected before they run, so the repo is never touched
+            let command = invoke.message.command().to_string();
+            if git::MUTATING_COMMANDS.contains(&command.as_str())
+                && invoke
+                    .message
+                    .webview()
+                    .state::<git::ReadOnlyMode>()
+                    .is_enabled()
+            {
+                invoke.resolver.reject(format!(
+                    "ReadOnly: '{}' is disabled while read-only mode is on.",
+                    command
+                ));
+                return true;
+            }
+            handler(invoke)
+        })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
\ No newline at end of file
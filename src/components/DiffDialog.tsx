@@ -15,6 +15,17 @@ interface FileDiff {
   path: string;
   old_content: string;
   new_content: string;
+  status: string;
+  old_path?: string;
+  binary: boolean;
+  old_size?: number;
+  new_size?: number;
+}
+
+function formatSize(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
 }
 
 interface DiffDialogProps {
@@ -159,17 +170,28 @@ export function DiffDialog({
               <ScrollArea className="h-full">
                 <div className="p-4" onClick={(e) => e.stopPropagation()}>
                   <div className="mb-4">
-                    <h3 className="text-sm font-medium">{selectedDiff.path}</h3>
+                    <h3 className="text-sm font-medium">
+                      {selectedDiff.old_path
+                        ? `${selectedDiff.old_path} → ${selectedDiff.path}`
+                        : selectedDiff.path}
+                    </h3>
                   </div>
-                  <ReactDiffViewer
-                    oldValue={selectedDiff.old_content}
-                    newValue={selectedDiff.new_content}
-                    splitView={true}
-                    useDarkTheme={isDarkMode}
-                    compareMethod={DiffMethod.WORDS}
-                    hideLineNumbers={false}
-                    disableWordDiff={false}
-                  />
+                  {selectedDiff.binary ? (
+                    <p className="text-sm text-muted-foreground">
+                      Binary file changed ({formatSize(selectedDiff.old_size ?? 0)} →{" "}
+                      {formatSize(selectedDiff.new_size ?? 0)})
+                    </p>
+                  ) : (
+                    <ReactDiffViewer
+                      oldValue={selectedDiff.old_content}
+                      newValue={selectedDiff.new_content}
+                      splitView={true}
+                      useDarkTheme={isDarkMode}
+                      compareMethod={DiffMethod.WORDS}
+                      hideLineNumbers={false}
+                      disableWordDiff={false}
+                    />
+                  )}
                 </div>
               </ScrollArea>
             )}
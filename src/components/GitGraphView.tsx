@@ -157,12 +157,12 @@ export function GitGraphView({
         // But we need to handle the case where "Uncommitted Changes" is present.
         // "Uncommitted Changes" is NOT a real commit in git history, so git2 revwalk won't count it.
         // But our loadedCommits array INCLUDES it.
-        // So if loadedCommits has "working-copy", we should subtract 1 from skip.
+        // So if loadedCommits has the virtual working-copy node, we should subtract 1 from skip.
 
         let skip = isLoadMore ? loadedCommits.current.length : 0;
         if (
           isLoadMore &&
-          loadedCommits.current.some((c) => c.id === "working-copy")
+          loadedCommits.current.some((c) => c.is_virtual)
         ) {
           skip -= 1;
         }
@@ -896,11 +896,6 @@ export function GitGraphView({
     (event: React.MouseEvent, node: Node) => {
       // Diff mode handling
       if (diffMode.active && diffMode.sourceCommitId !== null) {
-        // Skip if trying to diff with "working-copy"
-        if (node.id === "working-copy") {
-          return;
-        }
-
         event.stopPropagation();
 
         console.log("Node clicked in diff mode:", node.id);
@@ -67,7 +67,7 @@ export function CommitNode(props: NodeProps) {
     typedData.commit?.parents && typedData.commit.parents.length > 1;
   const isRoot =
     !typedData.commit?.parents || typedData.commit.parents.length === 0;
-  const isUncommitted = typedData.commit?.id === "working-copy";
+  const isUncommitted = typedData.commit?.is_virtual === true;
   const uncommittedState = typedData.commit?.uncommitted_state;
   const isStash = typedData.commit?.refs?.some((r: string) =>
     r.startsWith("stash@"),
@@ -3,8 +3,17 @@ import { Node, Edge } from "@xyflow/react";
 
 export interface GitCommit {
   id: string;
+  /** Summary line of the commit message. */
   message: string;
+  /** The rest of the commit message, left out when there is none. */
+  body?: string;
   author: string;
+  author_email: string;
+  author_date: number;
+  committer: string;
+  committer_email: string;
+  committer_date: number;
+  /** Commit time, same as committer_date. */
   date: number;
   /**
    * Parent commit IDs. For merge commits, the first parent (parents[0]) is the main line,
@@ -19,6 +28,69 @@ export interface GitCommit {
    * - "mixed": both staged and unstaged changes
    */
   uncommitted_state?: string;
+  /**
+   * True for the uncommitted changes node, which is not a real commit.
+   * It can only be the new side of a diff and can't be checked out.
+   */
+  is_virtual: boolean;
+  /**
+   * Summaries of the parents loaded in the same page, only present when
+   * get_commits is called with parentSummaries.
+   */
+  parent_summaries?: { oid: string; summary: string }[];
+  /**
+   * Change counts. Present on the working-copy node (left out when there are
+   * too many changed files) and on every commit when get_commits is called
+   * with includeStats.
+   */
+  stats?: { files_changed: number; additions: number; deletions: number };
+}
+
+/**
+ * Response of get_commits_compact. Parent indices below commits.length point into
+ * commits, higher ones into external_parents.
+ */
+export interface CompactCommitResponse {
+  commits: {
+    id: string;
+    message: string;
+    body?: string;
+    author: number;
+    author_email: number;
+    author_date: number;
+    committer: number;
+    committer_email: number;
+    committer_date: number;
+    date: number;
+    parents: number[];
+    refs?: string[];
+    head_type?: string;
+    uncommitted_state?: string;
+    is_virtual?: boolean;
+    stats?: { files_changed: number; additions: number; deletions: number };
+  }[];
+  authors: string[];
+  external_parents: string[];
+  has_more: boolean;
+}
+
+/**
+ * Rebuilds the verbose commit list from a get_commits_compact response.
+ */
+export function expandCompactCommits(response: CompactCommitResponse): GitCommit[] {
+  const { commits, authors, external_parents } = response;
+  return commits.map((commit) => ({
+    ...commit,
+    author: authors[commit.author],
+    author_email: authors[commit.author_email],
+    committer: authors[commit.committer],
+    committer_email: authors[commit.committer_email],
+    parents: commit.parents.map((index) =>
+      index < commits.length ? commits[index].id : external_parents[index - commits.length],
+    ),
+    refs: commit.refs ?? [],
+    is_virtual: commit.is_virtual ?? false,
+  }));
 }
 
 export interface LayoutedElements {
@@ -88,7 +160,7 @@ export function getLayoutedElements(commits: GitCommit[]): LayoutedElements {
       commit.parents.forEach((parentId, index) => {
         if (commits.some((c) => c.id === parentId)) {
           // Use gray for edges pointing to uncommitted changes node
-          const stroke = commit.id === "working-copy" 
+          const stroke = commit.is_virtual 
             ? "#6b7280" // same gray as the node background
             : `hsl(${getAuthorHue(commit.author)}, 60%, 60%)`;
           const style: { stroke: string; strokeWidth?: number; strokeDasharray?: string } = { 
@@ -4,6 +4,9 @@ import { Node, Edge } from '@xyflow/react';
 export interface GitRef {
   name: string;
   commit_id: string;
+  // Only set by resolve_ref_full
+  full_name?: string;
+  kind?: "branch" | "remote" | "tag" | "other";
 }
 
 interface GitGraphState {
@@ -47,10 +50,11 @@ interface GitGraphState {
   setDiffTarget: (targetCommitId: string) => void;
   checkoutCommit: (repoPath: string, commitId: string) => Promise<void>;
   checkoutBranch: (repoPath: string, branchName: string) => Promise<void>;
+  fetchRemote: (repoPath: string, remoteName: string) => Promise<number>;
   pullBranch: (repoPath: string, branchName: string) => Promise<void>;
   pushBranch: (repoPath: string, branchName: string) => Promise<void>;
-  createBranch: (repoPath: string, branchName: string, commitId: string) => Promise<void>;
-  deleteBranch: (repoPath: string, branchName: string) => Promise<void>;
+  createBranch: (repoPath: string, branchName: string, commitId: string, checkout?: boolean) => Promise<void>;
+  deleteBranch: (repoPath: string, branchName: string, force?: boolean) => Promise<void>;
   deleteRemoteBranch: (repoPath: string, branchName: string) => Promise<void>;
   applyStash: (repoPath: string, stashRef: string) => Promise<void>;
   dropStash: (repoPath: string, stashRef: string) => Promise<void>;
@@ -176,6 +180,33 @@ export const useGitGraphStore = create<GitGraphState>((set, get) => ({
       setLoading(false);
     }
   },
+  fetchRemote: async (repoPath: string, remoteName: string) => {
+    if (!repoPath) {
+      throw new Error('No repository path provided');
+    }
+    
+    const { setLoading } = get();
+    
+    try {
+      setLoading(true, `fetching ${remoteName}`);
+      const { invoke } = await import('@tauri-apps/api/core');
+      const updates = await invoke<unknown[]>('fetch_remote', {
+        repoPath,
+        remoteName,
+      });
+      
+      // Trigger refresh so new remote commits show up in the graph
+      const { refreshGraph } = get();
+      refreshGraph();
+      
+      return updates.length;
+    } catch (error) {
+      console.error('Failed to fetch remote:', error);
+      throw error;
+    } finally {
+      setLoading(false);
+    }
+  },
   pullBranch: async (repoPath: string, branchName: string) => {
     if (!repoPath) {
       throw new Error('No repository path provided');
@@ -228,7 +259,7 @@ export const useGitGraphStore = create<GitGraphState>((set, get) => ({
       setLoading(false);
     }
   },
-  createBranch: async (repoPath: string, branchName: string, commitId: string) => {
+  createBranch: async (repoPath: string, branchName: string, commitId: string, checkout = false) => {
     if (!repoPath) {
       throw new Error('No repository path provided');
     }
@@ -242,6 +273,7 @@ export const useGitGraphStore = create<GitGraphState>((set, get) => ({
         repoPath,
         branchName,
         commitId,
+        checkout,
       });
       
       // Trigger refresh after successful branch creation
@@ -255,7 +287,7 @@ export const useGitGraphStore = create<GitGraphState>((set, get) => ({
       setLoading(false);
     }
   },
-  deleteBranch: async (repoPath: string, branchName: string) => {
+  deleteBranch: async (repoPath: string, branchName: string, force = false) => {
     if (!repoPath) {
       throw new Error('No repository path provided');
     }
@@ -268,6 +300,7 @@ export const useGitGraphStore = create<GitGraphState>((set, get) => ({
       await invoke('delete_branch', {
         repoPath,
         branchName,
+        force,
       });
       
       // Trigger refresh after successful branch deletion